@@ -1,11 +1,33 @@
-pub const CLASS_TYPES: usize = 3;
-pub const REWARD_DENOMIATOR: u64 = 10;
+pub const ROLE_TYPES: usize = 3;
+pub const MAX_MULTISIG_SIGNERS: usize = 5;
+pub const CLASS_NAME_LEN: usize = 32;
 
 pub const GLOBAL_AUTHORITY_SEED: &str = "global-authority";
 pub const RS_PREFIX: &str = "puffu-nft-staking";
 pub const RS_STAKEINFO_SEED: &str = "puffu-stake-info";
 pub const RS_STAKE_SEED: &str = "puffu-nft-staking";
 pub const RS_VAULT_SEED: &str = "puffu-vault";
+pub const RS_USER_STATE_SEED: &str = "puffu-user-state";
+pub const RS_PROPOSAL_SEED: &str = "puffu-proposal";
+pub const RS_CLASS_SEED: &str = "puffu-class";
 
 pub const DAY: i64 = 60 * 60 * 24;
-pub const DECIMAL: u32 = 9;
+pub const ACC_PRECISION: u128 = 1_000_000_000_000;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+// longest config delay the admin can set
+pub const MAX_CONFIG_DELAY: i64 = 90 * DAY;
+
+// pause flags, each one stops the matching operations
+pub const PAUSE_STAKE: u8 = 1 << 0;
+pub const PAUSE_CLAIM: u8 = 1 << 1;
+pub const PAUSE_WITHDRAW: u8 = 1 << 2;
+pub const PAUSE_DEPOSIT: u8 = 1 << 3;
+// stops reward accrual
+pub const PAUSE_ACCRUAL: u8 = 1 << 4;
+pub const PAUSE_ALL: u8 =
+    PAUSE_STAKE | PAUSE_CLAIM | PAUSE_WITHDRAW | PAUSE_DEPOSIT | PAUSE_ACCRUAL;
+
+// max nfts per batch instruction, keeps each one within the compute budget
+pub const MAX_STAKE_BATCH: usize = 4;
+pub const MAX_CLAIM_BATCH: usize = 10;
+pub const MAX_WITHDRAW_BATCH: usize = 4;
@@ -18,4 +18,74 @@ pub enum StakingError {
     IndexOverflow,
     #[msg("Insufficient Lamports")]
     LackLamports,
+    #[msg("NFT is not a verified member of the pool collection")]
+    InvalidCollection,
+    #[msg("Class account does not match the class id")]
+    InvalidClassId,
+    #[msg("Class proof does not match the published root")]
+    InvalidClassProof,
+    #[msg("Token account must hold exactly one NFT")]
+    InvalidNftAmount,
+    #[msg("Reward vault cannot cover the owed reward")]
+    InsufficientRewardVault,
+    #[msg("Amount exceeds the vault surplus over owed rewards and runway")]
+    InsufficientSurplus,
+    #[msg("Invalid number of NFTs or accounts in batch")]
+    InvalidBatchSize,
+    #[msg("Signer is not in the pool multisig")]
+    NotMultisigSigner,
+    #[msg("Multisig needs distinct signers and a reachable threshold")]
+    InvalidMultisig,
+    #[msg("Proposal does not match the pool or accounts")]
+    InvalidProposal,
+    #[msg("Proposal already approved by this signer")]
+    AlreadyApproved,
+    #[msg("Proposal already executed")]
+    ProposalExecuted,
+    #[msg("Multisig changed since the proposal was created")]
+    StaleProposal,
+    #[msg("Proposal has not reached the approval threshold")]
+    ThresholdNotMet,
+    #[msg("No config change queued")]
+    NoQueuedConfig,
+    #[msg("Config change delay has not passed")]
+    ConfigChangeLocked,
+    #[msg("Config delay can only be increased, up to MAX_CONFIG_DELAY")]
+    InvalidConfigDelay,
+    #[msg("Operation is paused")]
+    OperationPaused,
+    #[msg("Unknown pause flags")]
+    InvalidPauseFlags,
+    #[msg("Withdrawals are not paused")]
+    WithdrawNotPaused,
+    #[msg("Penalty cannot exceed 10000 bps")]
+    InvalidPenalty,
+    #[msg("Early-unstake penalty cannot be combined with a cooldown")]
+    PenaltyWithCooldown,
+    #[msg("Treasury must be a token account of the reward mint")]
+    InvalidTreasury,
+    #[msg("Stake is already unbonding")]
+    AlreadyUnbonding,
+    #[msg("Request unstake and wait out the cooldown first")]
+    UnbondingRequired,
+    #[msg("Unbonding cooldown has not passed")]
+    CooldownNotOver,
+    #[msg("Reward decimals can only change with per-nft rewards and no unpaid debt")]
+    RewardDecimalsMismatch,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Reward denominator must be positive")]
+    InvalidRewardDenominator,
+    #[msg("Class does not take new stakes")]
+    ClassDisabled,
+    #[msg("Class reached its max stakers")]
+    ClassFull,
+    #[msg("Pool reached its max staked nfts")]
+    PoolFull,
+    #[msg("Wallet reached the max staked nfts of the pool")]
+    WalletLimitReached,
+    #[msg("Class has not waited out the config delay yet")]
+    ClassNotActive,
+    #[msg("Reward of a class with an early-unstake penalty can only be claimed after the lock")]
+    RewardLocked,
 }
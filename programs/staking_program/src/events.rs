@@ -0,0 +1,319 @@
+use crate::{
+    constants::*,
+    states::{AdminAction, ClassParams, Role},
+};
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct PoolInitialized {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub reward_mint: Pubkey,
+    pub collection: Pubkey,
+    pub config_delay: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NftStaked {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub class_id: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NftWithdrawn {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub class_id: u32,
+    /// Reward paid out together with the nft
+    pub reward: u64,
+    /// Reward cut for unstaking before the lock period ended
+    pub penalty: u64,
+    /// Reward the wallet is owed because the vault was short
+    pub unpaid_reward: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardClaimed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub class_id: u32,
+    pub amount: u64,
+    /// Reward still owed to the stake because the vault was short
+    pub unpaid_reward: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnpaidRewardClaimed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    /// Reward still owed to the wallet because the vault was short
+    pub unpaid_reward: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardDeposited {
+    pub pool: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardWithdrawn {
+    pub pool: Pubkey,
+    pub treasurer: Pubkey,
+    pub amount: u64,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeQueued {
+    pub pool: Pubkey,
+    pub config_manager: Pubkey,
+    pub runway_day: u16,
+    pub emission_per_second: u64,
+    pub max_staked_nft: u32,
+    pub max_staked_per_wallet: u32,
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigDelayChanged {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub config_delay: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolConfigChanged {
+    pub pool: Pubkey,
+    pub executor: Pubkey,
+    pub runway_day: u16,
+    pub emission_per_second: u64,
+    pub max_staked_nft: u32,
+    pub max_staked_per_wallet: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClassRootChangeQueued {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub class_root: [u8; 32],
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClassRootChangeCancelled {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClassRootChanged {
+    pub pool: Pubkey,
+    pub executor: Pubkey,
+    pub class_root: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardMintChanged {
+    pub pool: Pubkey,
+    pub treasurer: Pubkey,
+    pub old_mint: Pubkey,
+    pub new_mint: Pubkey,
+    /// Reserved reward the new vault was funded with, in new mint units
+    pub funded_amount: u64,
+    /// Old vault balance returned to the treasurer
+    pub returned_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OwnershipTransferStarted {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OwnershipTransferCancelled {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OwnershipTransferred {
+    pub pool: Pubkey,
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseChanged {
+    pub pool: Pubkey,
+    pub pauser: Pubkey,
+    pub pause_flags: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub role: Role,
+    pub account: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub role: Role,
+    pub account: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub pool: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub action: AdminAction,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalApproved {
+    pub pool: Pubkey,
+    pub proposal: Pubkey,
+    pub signer: Pubkey,
+    pub approvals: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub pool: Pubkey,
+    pub proposal: Pubkey,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MultisigChanged {
+    pub pool: Pubkey,
+    pub signers: [Pubkey; MAX_MULTISIG_SIGNERS],
+    pub threshold: u8,
+    pub version: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmergencyWithdrawn {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub class_id: u32,
+    pub forfeited_reward: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PenaltyTreasuryChanged {
+    pub pool: Pubkey,
+    pub treasurer: Pubkey,
+    pub penalty_treasury: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnstakeRequested {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub class_id: u32,
+    /// When the nft can be withdrawn
+    pub cooldown_end: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClassAdded {
+    pub pool: Pubkey,
+    pub class: Pubkey,
+    pub class_id: u32,
+    pub config_manager: Pubkey,
+    pub params: ClassParams,
+    pub active_from: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClassUpdateQueued {
+    pub pool: Pubkey,
+    pub class_id: u32,
+    pub config_manager: Pubkey,
+    pub params: ClassParams,
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClassUpdateCancelled {
+    pub pool: Pubkey,
+    pub class_id: u32,
+    pub authority: Pubkey,
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClassUpdated {
+    pub pool: Pubkey,
+    pub class_id: u32,
+    pub executor: Pubkey,
+    pub params: ClassParams,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClassEnabledChanged {
+    pub pool: Pubkey,
+    pub class_id: u32,
+    pub config_manager: Pubkey,
+    pub enabled: bool,
+    pub timestamp: i64,
+}
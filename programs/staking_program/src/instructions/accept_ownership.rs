@@ -0,0 +1,49 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptOwnership<'info> {
+    #[account(mut)]
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = pool_account.pending_admin == new_admin.key() @ StakingError::NotAllowedAuthority,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+}
+
+/**
+ * Take over the pool, roles still held by the previous admin move to the new admin.
+ */
+pub fn handle(ctx: Context<AcceptOwnership>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let pool_account = &mut ctx.accounts.pool_account;
+    let previous_admin = pool_account.admin;
+    pool_account.admin = pool_account.pending_admin;
+    pool_account.pending_admin = Pubkey::default();
+
+    for role in [Role::Pauser, Role::Treasurer, Role::ConfigManager].iter() {
+        if pool_account.has_role(*role, &previous_admin) {
+            pool_account.role_holders[*role as usize] = pool_account.admin;
+            emit!(RoleGranted {
+                pool: pool_account.key(),
+                admin: pool_account.admin,
+                role: *role,
+                account: pool_account.admin,
+                timestamp,
+            });
+        }
+    }
+
+    emit!(OwnershipTransferred {
+        pool: pool_account.key(),
+        previous_admin,
+        new_admin: pool_account.admin,
+        timestamp,
+    });
+    Ok(())
+}
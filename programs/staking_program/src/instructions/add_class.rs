@@ -0,0 +1,65 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AddClass<'info> {
+    #[account(mut)]
+    pub config_manager: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = pool_account.has_role(Role::ConfigManager, &config_manager.key()) @ StakingError::NotAllowedAuthority,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(
+        init,
+        seeds = [
+            RS_CLASS_SEED.as_bytes(),
+            pool_account.key().as_ref(),
+            pool_account.class_count.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = config_manager,
+        space = 8 + std::mem::size_of::<ClassConfig>(),
+    )]
+    pub class_config: Box<Account<'info, ClassConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Add an enabled class with the next class id, it takes stakes once `config_delay` has passed.
+ */
+pub fn handle(ctx: Context<AddClass>, params: ClassParams) -> Result<()> {
+    params.validate()?;
+    let timestamp = Clock::get()?.unix_timestamp;
+    let pool_account = &mut ctx.accounts.pool_account;
+    let class_config = &mut ctx.accounts.class_config;
+
+    class_config.pool = pool_account.key();
+    class_config.class_id = pool_account.class_count;
+    class_config.enabled = true;
+    class_config.active_from = timestamp
+        .checked_add(pool_account.config_delay)
+        .ok_or(StakingError::MathOverflow)?;
+    pool_account.set_class_params(class_config, &params, timestamp)?;
+    pool_account.class_count = pool_account
+        .class_count
+        .checked_add(1)
+        .ok_or(StakingError::MathOverflow)?;
+
+    emit!(ClassAdded {
+        pool: pool_account.key(),
+        class: class_config.key(),
+        class_id: class_config.class_id,
+        config_manager: ctx.accounts.config_manager.key(),
+        params,
+        active_from: class_config.active_from,
+        timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(
+        mut,
+        constraint = proposal.pool == pool_account.key() @ StakingError::InvalidProposal,
+        constraint = !proposal.executed @ StakingError::ProposalExecuted,
+        constraint = proposal.multisig_version == pool_account.multisig_version @ StakingError::StaleProposal,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+pub fn handle(ctx: Context<ApproveProposal>) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
+    let index = ctx
+        .accounts
+        .pool_account
+        .multisig_index(&signer)
+        .ok_or(StakingError::NotMultisigSigner)?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    require!(!proposal.approvals[index], StakingError::AlreadyApproved);
+    proposal.approvals[index] = true;
+
+    emit!(ProposalApproved {
+        pool: proposal.pool,
+        proposal: proposal.key(),
+        signer,
+        approvals: proposal.approval_count(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelClassRootChange<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = admin,
+        constraint = pool_account.is_initialized == true,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+}
+
+pub fn handle(ctx: Context<CancelClassRootChange>) -> Result<()> {
+    let pool_account = &mut ctx.accounts.pool_account;
+    let queued = pool_account
+        .queued_class_root
+        .take()
+        .ok_or(StakingError::NoQueuedConfig)?;
+
+    emit!(ClassRootChangeCancelled {
+        pool: pool_account.key(),
+        admin: ctx.accounts.admin.key(),
+        eta: queued.eta,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelClassUpdate<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = pool_account.admin == authority.key()
+            || pool_account.has_role(Role::ConfigManager, &authority.key()) @ StakingError::NotAllowedAuthority,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_CLASS_SEED.as_bytes(),
+            pool_account.key().as_ref(),
+            class_config.class_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub class_config: Box<Account<'info, ClassConfig>>,
+}
+
+pub fn handle(ctx: Context<CancelClassUpdate>) -> Result<()> {
+    let class_config = &mut ctx.accounts.class_config;
+    let update = class_config
+        .queued_update
+        .take()
+        .ok_or(StakingError::NoQueuedConfig)?;
+
+    emit!(ClassUpdateCancelled {
+        pool: ctx.accounts.pool_account.key(),
+        class_id: class_config.class_id,
+        authority: ctx.accounts.authority.key(),
+        eta: update.eta,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
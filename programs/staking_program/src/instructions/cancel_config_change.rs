@@ -0,0 +1,33 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = pool_account.admin == authority.key()
+            || pool_account.has_role(Role::ConfigManager, &authority.key()) @ StakingError::NotAllowedAuthority,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+}
+
+pub fn handle(ctx: Context<CancelConfigChange>) -> Result<()> {
+    let pool_account = &mut ctx.accounts.pool_account;
+    let config = pool_account
+        .queued_config
+        .take()
+        .ok_or(StakingError::NoQueuedConfig)?;
+
+    emit!(ConfigChangeCancelled {
+        pool: pool_account.key(),
+        authority: ctx.accounts.authority.key(),
+        eta: config.eta,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use crate::{constants::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+// only run through `execute_proposal`, so the admin being replaced cannot cancel it
+#[derive(Accounts)]
+pub struct CancelOwnershipTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+}
+
+pub fn handle(ctx: Context<CancelOwnershipTransfer>) -> Result<()> {
+    let pool_account = &mut ctx.accounts.pool_account;
+    let pending_admin = pool_account.pending_admin;
+    pool_account.pending_admin = Pubkey::default();
+
+    emit!(OwnershipTransferCancelled {
+        pool: pool_account.key(),
+        admin: pool_account.admin,
+        pending_admin,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use crate::{constants::*, error::*, events::*, states::*, utils::*};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+// remaining accounts per nft: nft_mint, nft_stake_info_account (mut), class_config
+const CLAIM_MANY_ACCOUNTS: usize = 3;
+
+#[derive(Accounts)]
+pub struct ClaimMany<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = !pool_account.is_paused(PAUSE_CLAIM) @ StakingError::OperationPaused,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_USER_STATE_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        mut,
+        address = pool_account.reward_vault,
+        token::mint = reward_mint,
+        token::authority = pool_account,
+    )]
+    reward_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = pool_account.reward_mint)]
+    pub reward_mint: Account<'info, Mint>,
+
+    // send reward to user reward vault
+    #[account(
+      init_if_needed,
+      payer = owner,
+      associated_token::mint = reward_mint,
+      associated_token::authority = owner
+    )]
+    reward_to_account: Box<Account<'info, TokenAccount>>,
+
+    // The Token Program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/**
+ * Claim the reward of several staked nfts with a single transfer.
+ */
+pub fn claim_many<'info>(ctx: Context<'_, '_, '_, 'info, ClaimMany<'info>>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let remaining_accounts = ctx.remaining_accounts;
+    let count = remaining_accounts.len() / CLAIM_MANY_ACCOUNTS;
+    require!(
+        count > 0
+            && count <= MAX_CLAIM_BATCH
+            && remaining_accounts.len() == count * CLAIM_MANY_ACCOUNTS,
+        StakingError::InvalidBatchSize
+    );
+
+    let owner = ctx.accounts.owner.key();
+    let pool_key = ctx.accounts.pool_account.key();
+    let pool_account = &mut ctx.accounts.pool_account;
+    let user_state = &mut ctx.accounts.user_state;
+    let mut vault_balance = ctx.accounts.reward_vault.amount;
+
+    // debt of withdrawn nfts is paid before the staked ones
+    let mut reward = pool_account.pay_user_debt(user_state, vault_balance)?;
+    vault_balance -= reward;
+    if reward > 0 {
+        emit!(UnpaidRewardClaimed {
+            pool: pool_key,
+            user: owner,
+            amount: reward,
+            unpaid_reward: user_state.unpaid_reward,
+            timestamp,
+        });
+    }
+
+    let mut classes = ClassCache::default();
+    for accounts in remaining_accounts.chunks(CLAIM_MANY_ACCOUNTS) {
+        let nft_mint = &accounts[0];
+        let mut staking_info = Account::<StakeInfo>::try_from(&accounts[1])?;
+
+        let (stake_info_key, _bump) = Pubkey::find_program_address(
+            &[
+                RS_STAKEINFO_SEED.as_ref(),
+                pool_key.as_ref(),
+                nft_mint.key().as_ref(),
+            ],
+            ctx.program_id,
+        );
+        require!(
+            stake_info_key == staking_info.key(),
+            StakingError::InvalidNFTAddress
+        );
+        require!(
+            staking_info.owner == owner,
+            StakingError::InvalidUserAddress
+        );
+
+        // unpaid debt is paid first, any shortfall is carried to the next claim
+        let class = classes.load(&accounts[2], &pool_key, staking_info.class_id)?;
+        let nft_reward = pool_account.claim(&mut staking_info, class, timestamp, vault_balance)?;
+        vault_balance -= nft_reward;
+        reward = reward
+            .checked_add(nft_reward)
+            .ok_or(StakingError::MathOverflow)?;
+        staking_info.exit(ctx.program_id)?;
+
+        emit!(RewardClaimed {
+            pool: pool_key,
+            user: owner,
+            mint: staking_info.nft_addr,
+            class_id: staking_info.class_id,
+            amount: nft_reward,
+            unpaid_reward: staking_info.unpaid_reward,
+            timestamp,
+        });
+    }
+
+    user_state.record_claim(reward, timestamp)?;
+
+    if reward > 0 {
+        let pool_id = pool_account.pool_id.to_le_bytes();
+        let (_pool_account_seed, _bump) =
+            Pubkey::find_program_address(&[RS_PREFIX.as_bytes(), &pool_id], ctx.program_id);
+        let pool_seeds = &[RS_PREFIX.as_bytes(), &pool_id, &[_bump]];
+        let signer = &[&pool_seeds[..]];
+
+        let token_accounts = anchor_spl::token::Transfer {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            to: ctx.accounts.reward_to_account.to_account_info(),
+            authority: ctx.accounts.pool_account.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), token_accounts);
+        msg!(
+            "Calling the token program to transfer reward {} to the user",
+            reward
+        );
+        anchor_spl::token::transfer(cpi_ctx.with_signer(signer), reward)?;
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::{constants::*, error::*, states::*};
+use crate::{constants::*, error::*, events::*, states::*};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -12,22 +12,48 @@ pub struct ClaimReward<'info> {
 
     #[account(
         mut,
-        seeds = [RS_PREFIX.as_bytes()],
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
         bump,
         constraint = pool_account.is_initialized == true,
-        constraint = pool_account.paused == false,
+        constraint = !pool_account.is_paused(PAUSE_CLAIM) @ StakingError::OperationPaused,
     )]
     pub pool_account: Account<'info, PoolConfig>,
 
     #[account(
         mut,
-        seeds = [RS_STAKEINFO_SEED.as_ref(), nft_mint.key().as_ref()],
+        seeds = [
+            RS_USER_STATE_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_STAKEINFO_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            nft_mint.key().as_ref(),
+        ],
         bump,
     )]
     pub nft_stake_info_account: Account<'info, StakeInfo>,
 
+    #[account(
+        seeds = [
+            RS_CLASS_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            nft_stake_info_account.class_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub class_config: Box<Account<'info, ClassConfig>>,
+
     #[account(
         mut,
+        address = pool_account.reward_vault,
         token::mint = reward_mint,
         token::authority = pool_account,
     )]
@@ -71,21 +97,46 @@ pub fn claim_reward(ctx: Context<ClaimReward>) -> Result<()> {
 
     // calulate reward of this nft
     let pool_account = &mut ctx.accounts.pool_account;
-    let reward_per_day = pool_account.reward_policy_by_class[staking_info.class_id as usize];
-    // When withdraw nft, calculate and send reward SWRD
-    let mut reward: u64 = staking_info.update_reward(timestamp, reward_per_day)?;
-
+    let user_state = &mut ctx.accounts.user_state;
+    // unpaid debt is paid first, any shortfall is carried to the next claim
     let vault_balance = ctx.accounts.reward_vault.amount;
-
-    if vault_balance < reward {
-        reward = vault_balance;
+    let debt = pool_account.pay_user_debt(user_state, vault_balance)?;
+    let nft_reward = pool_account.claim(
+        staking_info,
+        &ctx.accounts.class_config,
+        timestamp,
+        vault_balance - debt,
+    )?;
+    let reward = debt
+        .checked_add(nft_reward)
+        .ok_or(StakingError::MathOverflow)?;
+    user_state.record_claim(reward, timestamp)?;
+
+    if debt > 0 {
+        emit!(UnpaidRewardClaimed {
+            pool: pool_account.key(),
+            user: staking_info.owner,
+            amount: debt,
+            unpaid_reward: user_state.unpaid_reward,
+            timestamp,
+        });
     }
+    emit!(RewardClaimed {
+        pool: pool_account.key(),
+        user: staking_info.owner,
+        mint: staking_info.nft_addr,
+        class_id: staking_info.class_id,
+        amount: nft_reward,
+        unpaid_reward: staking_info.unpaid_reward,
+        timestamp,
+    });
 
     // Transfer rewards from the pool reward vaults to user reward vaults.
+    let pool_id = pool_account.pool_id.to_le_bytes();
     let (_pool_account_seed, _bump) =
-        Pubkey::find_program_address(&[&(RS_PREFIX.as_bytes())], ctx.program_id);
+        Pubkey::find_program_address(&[RS_PREFIX.as_bytes(), &pool_id], ctx.program_id);
     // let bump = ctx.bumps.get(RS_PREFIX).unwrap();
-    let pool_seeds = &[RS_PREFIX.as_bytes(), &[_bump]];
+    let pool_seeds = &[RS_PREFIX.as_bytes(), &pool_id, &[_bump]];
     let signer = &[&pool_seeds[..]];
 
     let token_program = ctx.accounts.token_program.to_account_info().clone();
@@ -0,0 +1,100 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct ClaimUnpaid<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = !pool_account.is_paused(PAUSE_CLAIM) @ StakingError::OperationPaused,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_USER_STATE_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        mut,
+        address = pool_account.reward_vault,
+        token::mint = reward_mint,
+        token::authority = pool_account,
+    )]
+    reward_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = pool_account.reward_mint)]
+    pub reward_mint: Account<'info, Mint>,
+
+    // send reward to user reward vault
+    #[account(
+      init_if_needed,
+      payer = owner,
+      associated_token::mint = reward_mint,
+      associated_token::authority = owner
+    )]
+    reward_to_account: Box<Account<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/**
+ * Pay the reward of withdrawn nfts the vault could not cover,
+ * for wallets that have no staked nft left to claim with.
+ */
+pub fn claim_unpaid(ctx: Context<ClaimUnpaid>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let pool_account = &mut ctx.accounts.pool_account;
+    let user_state = &mut ctx.accounts.user_state;
+
+    let vault_balance = ctx.accounts.reward_vault.amount;
+    let reward = pool_account.pay_user_debt(user_state, vault_balance)?;
+    user_state.record_claim(reward, timestamp)?;
+
+    emit!(UnpaidRewardClaimed {
+        pool: pool_account.key(),
+        user: ctx.accounts.owner.key(),
+        amount: reward,
+        unpaid_reward: user_state.unpaid_reward,
+        timestamp,
+    });
+
+    if reward > 0 {
+        let pool_id = pool_account.pool_id.to_le_bytes();
+        let (_pool_account_seed, _bump) =
+            Pubkey::find_program_address(&[RS_PREFIX.as_bytes(), &pool_id], ctx.program_id);
+        let pool_seeds = &[RS_PREFIX.as_bytes(), &pool_id, &[_bump]];
+        let signer = &[&pool_seeds[..]];
+
+        let token_accounts = anchor_spl::token::Transfer {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            to: ctx.accounts.reward_to_account.to_account_info(),
+            authority: ctx.accounts.pool_account.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), token_accounts);
+        msg!(
+            "Calling the token program to transfer reward {} to the user",
+            reward
+        );
+        anchor_spl::token::transfer(cpi_ctx.with_signer(signer), reward)?;
+    }
+    Ok(())
+}
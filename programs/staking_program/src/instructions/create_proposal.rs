@@ -0,0 +1,68 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = pool_account.multisig_index(&proposer.key()).is_some() @ StakingError::NotMultisigSigner,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(
+        init,
+        seeds = [
+            RS_PROPOSAL_SEED.as_bytes(),
+            pool_account.key().as_ref(),
+            pool_account.proposal_count.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = proposer,
+        space = 8 + std::mem::size_of::<Proposal>(),
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Propose an admin action to the pool multisig, counting the proposer's approval.
+ */
+pub fn handle(ctx: Context<CreateProposal>, action: AdminAction) -> Result<()> {
+    if let AdminAction::ChangeMultisig { signers, threshold } = action {
+        PoolConfig::validate_multisig(&signers, threshold)?;
+    }
+
+    let pool_account = &mut ctx.accounts.pool_account;
+    let proposal = &mut ctx.accounts.proposal;
+    let proposer = ctx.accounts.proposer.key();
+    let index = pool_account.multisig_index(&proposer).unwrap();
+
+    proposal.pool = pool_account.key();
+    proposal.proposal_id = pool_account.proposal_count;
+    proposal.proposer = proposer;
+    proposal.action = action;
+    proposal.approvals[index] = true;
+    proposal.multisig_version = pool_account.multisig_version;
+    proposal.created_at = Clock::get()?.unix_timestamp;
+    pool_account.proposal_count = pool_account
+        .proposal_count
+        .checked_add(1)
+        .ok_or(StakingError::MathOverflow)?;
+
+    emit!(ProposalCreated {
+        pool: pool_account.key(),
+        proposal: proposal.key(),
+        proposal_id: proposal.proposal_id,
+        proposer,
+        action,
+        timestamp: proposal.created_at,
+    });
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::{constants::*, states::*};
+use crate::{constants::*, error::*, events::*, states::*};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 
@@ -9,14 +9,16 @@ pub struct DepositSwrd<'info> {
 
     #[account(
         mut,
-        seeds = [RS_PREFIX.as_bytes()],
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
         bump,
         constraint = pool_account.is_initialized == true,
+        constraint = !pool_account.is_paused(PAUSE_DEPOSIT) @ StakingError::OperationPaused,
     )]
     pub pool_account: Account<'info, PoolConfig>,
 
     #[account(
         mut,
+        address = pool_account.reward_vault,
         token::mint = reward_mint,
         token::authority = pool_account,
     )]
@@ -46,5 +48,13 @@ pub fn handle(ctx: Context<DepositSwrd>, amount: u64) -> Result<()> {
 
     anchor_spl::token::transfer(cpi_ctx, amount)?;
 
+    ctx.accounts.reward_vault.reload()?;
+    emit!(RewardDeposited {
+        pool: ctx.accounts.pool_account.key(),
+        funder: ctx.accounts.funder.key(),
+        amount,
+        vault_balance: ctx.accounts.reward_vault.amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
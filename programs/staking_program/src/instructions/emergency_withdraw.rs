@@ -0,0 +1,141 @@
+use crate::{constants::*, error::*, events::*, states::*, utils::*};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use mpl_token_metadata::state::{EDITION, PREFIX as METADATA_PREFIX};
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = pool_account.is_paused(PAUSE_WITHDRAW) @ StakingError::WithdrawNotPaused,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_USER_STATE_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_STAKEINFO_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            nft_mint.key().as_ref(),
+        ],
+        bump,
+        close = owner,
+        constraint = nft_stake_info_account.owner == owner.key() @ StakingError::InvalidUserAddress,
+    )]
+    pub nft_stake_info_account: Account<'info, StakeInfo>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_CLASS_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            nft_stake_info_account.class_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub class_config: Box<Account<'info, ClassConfig>>,
+
+    #[account(
+        mut,
+        constraint = user_nft_token_account.mint == nft_mint.key() @ StakingError::InvalidNFTAddress,
+        constraint = user_nft_token_account.owner == owner.key() @ StakingError::InvalidOwner,
+    )]
+    pub user_nft_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_STAKE_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            nft_mint.key().as_ref(),
+        ],
+        bump,
+        token::mint = nft_mint,
+        token::authority = pool_account,
+        constraint = pool_account.custody_mode != CustodyMode::Escrow
+            || staked_nft_token_account.amount == 1 @ StakingError::InvalidNftAmount,
+    )]
+    pub staked_nft_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: address is the Metaplex master edition PDA of `nft_mint`, used to thaw in `Freeze` mode
+    #[account(
+        seeds = [
+            METADATA_PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            nft_mint.key().as_ref(),
+            EDITION.as_bytes(),
+        ],
+        bump,
+        seeds::program = mpl_token_metadata::id(),
+    )]
+    pub nft_edition: UncheckedAccount<'info>,
+
+    /// CHECK: address is checked against the Metaplex token metadata program id
+    #[account(address = mpl_token_metadata::id())]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/**
+ * Get the NFT back while withdrawals are paused, ignoring the lock period.
+ * The stake's reward is forfeited, so the reward vault is not touched.
+ */
+pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let staking_info = &mut ctx.accounts.nft_stake_info_account;
+    let pool_account = &mut ctx.accounts.pool_account;
+    let forfeited_reward = pool_account.forfeit(
+        staking_info,
+        &mut ctx.accounts.class_config,
+        &mut ctx.accounts.user_state,
+        timestamp,
+    )?;
+
+    let pool_id = pool_account.pool_id.to_le_bytes();
+    let (_pool_account_seed, _pool_account_bump) =
+        Pubkey::find_program_address(&[RS_PREFIX.as_bytes(), &pool_id], ctx.program_id);
+    let seeds = &[RS_PREFIX.as_bytes(), &pool_id, &[_pool_account_bump]];
+    let signer = &[&seeds[..]];
+
+    let custody = NftCustody {
+        owner: ctx.accounts.owner.to_account_info(),
+        pool_account: ctx.accounts.pool_account.to_account_info(),
+        nft_mint: ctx.accounts.nft_mint.to_account_info(),
+        nft_edition: ctx.accounts.nft_edition.to_account_info(),
+        user_nft_token_account: ctx.accounts.user_nft_token_account.to_account_info(),
+        staked_nft_token_account: ctx.accounts.staked_nft_token_account.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+    };
+    custody.release(ctx.accounts.pool_account.custody_mode, signer)?;
+
+    emit!(EmergencyWithdrawn {
+        pool: ctx.accounts.pool_account.key(),
+        user: ctx.accounts.owner.key(),
+        mint: ctx.accounts.nft_mint.key(),
+        class_id: ctx.accounts.nft_stake_info_account.class_id,
+        forfeited_reward,
+        timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExecuteClassRootChange<'info> {
+    // anyone can apply a root once it has waited out the delay
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+}
+
+pub fn handle(ctx: Context<ExecuteClassRootChange>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let pool_account = &mut ctx.accounts.pool_account;
+    let queued = pool_account
+        .queued_class_root
+        .ok_or(StakingError::NoQueuedConfig)?;
+    require!(timestamp >= queued.eta, StakingError::ConfigChangeLocked);
+
+    pool_account.class_root = queued.class_root;
+    pool_account.queued_class_root = None;
+
+    emit!(ClassRootChanged {
+        pool: pool_account.key(),
+        executor: ctx.accounts.executor.key(),
+        class_root: queued.class_root,
+        timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExecuteClassUpdate<'info> {
+    // anyone can apply an update once it has waited out the delay
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_CLASS_SEED.as_bytes(),
+            pool_account.key().as_ref(),
+            class_config.class_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub class_config: Box<Account<'info, ClassConfig>>,
+}
+
+pub fn handle(ctx: Context<ExecuteClassUpdate>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let class_config = &mut ctx.accounts.class_config;
+    let update = class_config
+        .queued_update
+        .take()
+        .ok_or(StakingError::NoQueuedConfig)?;
+    require!(timestamp >= update.eta, StakingError::ConfigChangeLocked);
+
+    ctx.accounts
+        .pool_account
+        .set_class_params(class_config, &update.params, timestamp)?;
+
+    emit!(ClassUpdated {
+        pool: ctx.accounts.pool_account.key(),
+        class_id: class_config.class_id,
+        executor: ctx.accounts.executor.key(),
+        params: update.params,
+        timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    // anyone can apply a change once it has waited out the delay
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+}
+
+pub fn handle(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let pool_account = &mut ctx.accounts.pool_account;
+    let config = pool_account
+        .queued_config
+        .ok_or(StakingError::NoQueuedConfig)?;
+    require!(timestamp >= config.eta, StakingError::ConfigChangeLocked);
+
+    // accrue at the old emission before it changes
+    pool_account.update_pool(timestamp)?;
+    pool_account.runway_day = config.runway_day;
+    pool_account.emission_per_second = config.emission_per_second;
+    pool_account.max_staked_nft = config.max_staked_nft;
+    pool_account.max_staked_per_wallet = config.max_staked_per_wallet;
+    pool_account.queued_config = None;
+
+    emit!(PoolConfigChanged {
+        pool: pool_account.key(),
+        executor: ctx.accounts.executor.key(),
+        runway_day: config.runway_day,
+        emission_per_second: config.emission_per_second,
+        max_staked_nft: config.max_staked_nft,
+        max_staked_per_wallet: config.max_staked_per_wallet,
+        timestamp,
+    });
+    Ok(())
+}
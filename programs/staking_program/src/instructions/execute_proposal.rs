@@ -0,0 +1,135 @@
+use crate::{constants::*, error::*, events::*, instructions::*, states::*};
+use anchor_lang::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = pool_account.multisig_index(&executor.key()).is_some() @ StakingError::NotMultisigSigner,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(
+        mut,
+        constraint = proposal.pool == pool_account.key() @ StakingError::InvalidProposal,
+        constraint = !proposal.executed @ StakingError::ProposalExecuted,
+        constraint = proposal.multisig_version == pool_account.multisig_version @ StakingError::StaleProposal,
+        constraint = proposal.approval_count() >= pool_account.multisig_threshold @ StakingError::ThresholdNotMet,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+/**
+ * Run an approved proposal.
+ * The accounts of the wrapped instruction (`withdraw_swrd`, `change_reward_mint`,
+ * `transfer_ownership` or `cancel_ownership_transfer`) are passed as remaining accounts
+ * and checked by its own constraints.
+ * The proposal authorizes the action and names the treasurer receiving tokens,
+ * the treasurer or admin does not have to sign.
+ */
+pub fn handle<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>) -> Result<()> {
+    ctx.accounts.proposal.executed = true;
+
+    let pool_key = ctx.accounts.pool_account.key();
+    let mut accounts: &[AccountInfo<'info>] = ctx.remaining_accounts;
+    let mut bumps = BTreeMap::new();
+    match ctx.accounts.proposal.action {
+        AdminAction::WithdrawReward { amount, treasurer } => {
+            let mut inner =
+                WithdrawSwrd::try_accounts(ctx.program_id, &mut accounts, &[], &mut bumps)?;
+            require!(
+                inner.pool_account.key() == pool_key && inner.treasurer.key() == treasurer,
+                StakingError::InvalidProposal
+            );
+            withdraw_reward::handle(
+                Context::new(ctx.program_id, &mut inner, accounts, bumps),
+                amount,
+            )?;
+            inner.exit(ctx.program_id)?;
+            // pick up what the wrapped instruction wrote before this copy is persisted
+            ctx.accounts.pool_account.reload()?;
+        }
+        AdminAction::ChangeRewardMint {
+            new_mint,
+            treasurer,
+        } => {
+            let mut inner =
+                ChangeRewardMint::try_accounts(ctx.program_id, &mut accounts, &[], &mut bumps)?;
+            require!(
+                inner.pool_account.key() == pool_key
+                    && inner.new_reward_mint.key() == new_mint
+                    && inner.treasurer.key() == treasurer,
+                StakingError::InvalidProposal
+            );
+            update_token_mint::handle(Context::new(ctx.program_id, &mut inner, accounts, bumps))?;
+            inner.exit(ctx.program_id)?;
+            ctx.accounts.pool_account.reload()?;
+        }
+        AdminAction::TransferOwnership { new_admin } => {
+            let mut inner =
+                TransferOwnership::try_accounts(ctx.program_id, &mut accounts, &[], &mut bumps)?;
+            require!(
+                inner.pool_account.key() == pool_key,
+                StakingError::InvalidProposal
+            );
+            transfer_ownership::handle(
+                Context::new(ctx.program_id, &mut inner, accounts, bumps),
+                new_admin,
+            )?;
+            inner.exit(ctx.program_id)?;
+            ctx.accounts.pool_account.reload()?;
+        }
+        AdminAction::CancelOwnershipTransfer => {
+            let mut inner = CancelOwnershipTransfer::try_accounts(
+                ctx.program_id,
+                &mut accounts,
+                &[],
+                &mut bumps,
+            )?;
+            require!(
+                inner.pool_account.key() == pool_key,
+                StakingError::InvalidProposal
+            );
+            cancel_ownership_transfer::handle(Context::new(
+                ctx.program_id,
+                &mut inner,
+                accounts,
+                bumps,
+            ))?;
+            inner.exit(ctx.program_id)?;
+            ctx.accounts.pool_account.reload()?;
+        }
+        AdminAction::ChangeMultisig { signers, threshold } => {
+            PoolConfig::validate_multisig(&signers, threshold)?;
+            let pool_account = &mut ctx.accounts.pool_account;
+            pool_account.multisig_signers = signers;
+            pool_account.multisig_threshold = threshold;
+            pool_account.multisig_version = pool_account
+                .multisig_version
+                .checked_add(1)
+                .ok_or(StakingError::MathOverflow)?;
+
+            emit!(MultisigChanged {
+                pool: pool_key,
+                signers,
+                threshold,
+                version: pool_account.multisig_version,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+    }
+
+    emit!(ProposalExecuted {
+        pool: pool_key,
+        proposal: ctx.accounts.proposal.key(),
+        executor: ctx.accounts.executor.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
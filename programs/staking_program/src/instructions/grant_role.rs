@@ -0,0 +1,34 @@
+use crate::{constants::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct GrantRole<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = admin,
+        constraint = pool_account.is_initialized == true,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+}
+
+/**
+ * Hand `role` to `account`, replacing the previous holder.
+ */
+pub fn handle(ctx: Context<GrantRole>, role: Role, account: Pubkey) -> Result<()> {
+    let pool_account = &mut ctx.accounts.pool_account;
+    pool_account.role_holders[role as usize] = account;
+
+    emit!(RoleGranted {
+        pool: pool_account.key(),
+        admin: pool_account.admin,
+        role,
+        account,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
@@ -1,8 +1,9 @@
-use crate::{constants::*, error::*, states::*};
+use crate::{constants::*, error::*, events::*, states::*};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 
 #[derive(Accounts)]
+#[instruction(pool_id: u64)]
 pub struct InitializeStakingPool<'info> {
     // The pool owner
     #[account(mut)]
@@ -10,7 +11,7 @@ pub struct InitializeStakingPool<'info> {
 
     #[account(
         init,
-        seeds = [RS_PREFIX.as_bytes()],
+        seeds = [RS_PREFIX.as_bytes(), pool_id.to_le_bytes().as_ref()],
         bump,
         payer = admin,
         space = 8 + std::mem::size_of::<PoolConfig>(),
@@ -25,7 +26,11 @@ pub struct InitializeStakingPool<'info> {
         init,
         token::mint = reward_mint,
         token::authority = pool_account,
-        seeds = [ RS_VAULT_SEED.as_bytes(), reward_mint.key().as_ref() ],
+        seeds = [
+            RS_VAULT_SEED.as_bytes(),
+            pool_account.key().as_ref(),
+            reward_mint.key().as_ref(),
+        ],
         bump,
         payer = admin,
     )]
@@ -58,24 +63,54 @@ impl<'info> InitializeStakingPool<'info> {
  * Initialize Staking program for the first time to init staking pool config with some data for validation.
  */
 #[access_control(ctx.accounts.validate())]
+#[allow(clippy::too_many_arguments)]
 pub fn initialize_staking_pool(
     ctx: Context<InitializeStakingPool>,
-    reward_policy_by_class: [u16; CLASS_TYPES],
-    lock_day_by_class: [u16; CLASS_TYPES],
+    pool_id: u64,
+    collection: Pubkey,
+    class_root: [u8; 32],
+    custody_mode: CustodyMode,
+    reward_mode: RewardMode,
+    emission_per_second: u64,
+    config_delay: i64,
 ) -> Result<()> {
     msg!("initializing");
+    require!(
+        (0..=MAX_CONFIG_DELAY).contains(&config_delay),
+        StakingError::InvalidConfigDelay
+    );
 
     let pool_account = &mut ctx.accounts.pool_account;
 
     pool_account.is_initialized = true;
+    pool_account.pool_id = pool_id;
     pool_account.admin = *ctx.accounts.admin.key;
-    pool_account.paused = false; // initial status is paused
+    pool_account.pause_flags = 0; // initial status is unpaused
     pool_account.reward_mint = *ctx.accounts.reward_mint.to_account_info().key;
     pool_account.reward_vault = ctx.accounts.reward_vault.key();
+    pool_account.reward_decimals = ctx.accounts.reward_mint.decimals;
     pool_account.last_update_time = Clock::get()?.unix_timestamp;
     pool_account.staked_nft = 0;
     pool_account.lock_day = 0;
-    pool_account.lock_day_by_class = lock_day_by_class;
-    pool_account.reward_policy_by_class = reward_policy_by_class;
+    pool_account.collection = collection;
+    pool_account.class_root = class_root;
+    pool_account.custody_mode = custody_mode;
+    pool_account.reward_mode = reward_mode;
+    pool_account.emission_per_second = emission_per_second;
+    pool_account.role_holders = [pool_account.admin; ROLE_TYPES];
+    pool_account.multisig_signers[0] = pool_account.admin;
+    pool_account.multisig_threshold = 1;
+    // stakers see the delay before staking, afterwards it can only grow
+    pool_account.config_delay = config_delay;
+    pool_account.penalty_treasury = pool_account.reward_vault;
+
+    emit!(PoolInitialized {
+        pool: pool_account.key(),
+        admin: pool_account.admin,
+        reward_mint: pool_account.reward_mint,
+        collection,
+        config_delay,
+        timestamp: pool_account.last_update_time,
+    });
     Ok(())
 }
@@ -7,20 +7,98 @@ pub use stake::*;
 pub mod claim_reward;
 pub use claim_reward::*;
 
+pub mod request_unstake;
+pub use request_unstake::*;
+
 pub mod unstake;
 pub use unstake::*;
 
+pub mod emergency_withdraw;
+pub use emergency_withdraw::*;
+
+pub mod stake_many;
+pub use stake_many::*;
+
+pub mod claim_many;
+pub use claim_many::*;
+
+pub mod claim_unpaid;
+pub use claim_unpaid::*;
+
+pub mod withdraw_many;
+pub use withdraw_many::*;
+
 pub mod deposit_reward;
 pub use deposit_reward::*;
 
 pub mod withdraw_reward;
 pub use withdraw_reward::*;
 
-pub mod update_config;
-pub use update_config::*;
+pub mod queue_config_change;
+pub use queue_config_change::*;
+
+pub mod execute_config_change;
+pub use execute_config_change::*;
+
+pub mod cancel_config_change;
+pub use cancel_config_change::*;
+
+pub mod add_class;
+pub use add_class::*;
+
+pub mod queue_class_update;
+pub use queue_class_update::*;
+
+pub mod execute_class_update;
+pub use execute_class_update::*;
+
+pub mod cancel_class_update;
+pub use cancel_class_update::*;
+
+pub mod set_class_enabled;
+pub use set_class_enabled::*;
+
+pub mod update_config_delay;
+pub use update_config_delay::*;
+
+pub mod update_penalty_treasury;
+pub use update_penalty_treasury::*;
+
+pub mod set_paused;
+pub use set_paused::*;
+
+pub mod update_class_root;
+pub use update_class_root::*;
+
+pub mod execute_class_root_change;
+pub use execute_class_root_change::*;
+
+pub mod cancel_class_root_change;
+pub use cancel_class_root_change::*;
 
 pub mod update_token_mint;
 pub use update_token_mint::*;
 
 pub mod transfer_ownership;
 pub use transfer_ownership::*;
+
+pub mod accept_ownership;
+pub use accept_ownership::*;
+
+pub mod cancel_ownership_transfer;
+pub use cancel_ownership_transfer::*;
+
+pub mod grant_role;
+pub use grant_role::*;
+
+pub mod revoke_role;
+pub use revoke_role::*;
+
+pub mod create_proposal;
+pub use create_proposal::*;
+
+pub mod approve_proposal;
+pub use approve_proposal::*;
+
+pub mod execute_proposal;
+pub use execute_proposal::*;
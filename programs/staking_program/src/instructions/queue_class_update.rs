@@ -0,0 +1,50 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct QueueClassUpdate<'info> {
+    pub config_manager: Signer<'info>,
+
+    #[account(
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = pool_account.has_role(Role::ConfigManager, &config_manager.key()) @ StakingError::NotAllowedAuthority,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_CLASS_SEED.as_bytes(),
+            pool_account.key().as_ref(),
+            class_config.class_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub class_config: Box<Account<'info, ClassConfig>>,
+}
+
+/**
+ * Queue a class update that can be executed once the pool `config_delay` has passed.
+ * An update already in the queue is replaced and its delay starts over.
+ */
+pub fn handle(ctx: Context<QueueClassUpdate>, params: ClassParams) -> Result<()> {
+    params.validate()?;
+    let timestamp = Clock::get()?.unix_timestamp;
+    let eta = timestamp
+        .checked_add(ctx.accounts.pool_account.config_delay)
+        .ok_or(StakingError::MathOverflow)?;
+    let class_config = &mut ctx.accounts.class_config;
+    class_config.queued_update = Some(QueuedClassUpdate { params, eta });
+
+    emit!(ClassUpdateQueued {
+        pool: ctx.accounts.pool_account.key(),
+        class_id: class_config.class_id,
+        config_manager: ctx.accounts.config_manager.key(),
+        params,
+        eta,
+        timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+#[derive(Accounts)]
+pub struct QueueConfigChange<'info> {
+    #[account(mut)]
+    pub config_manager: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = pool_account.has_role(Role::ConfigManager, &config_manager.key()) @ StakingError::NotAllowedAuthority,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+}
+
+/**
+ * Queue a config change that can be executed once `config_delay` has passed.
+ * A change already in the queue is replaced and its delay starts over.
+ */
+pub fn handle(
+    ctx: Context<QueueConfigChange>,
+    runway_day: u16,
+    emission_per_second: u64,
+    max_staked_nft: u32,
+    max_staked_per_wallet: u32,
+) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let pool_account = &mut ctx.accounts.pool_account;
+    let eta = timestamp
+        .checked_add(pool_account.config_delay)
+        .ok_or(StakingError::MathOverflow)?;
+    pool_account.queued_config = Some(QueuedConfig {
+        runway_day,
+        emission_per_second,
+        max_staked_nft,
+        max_staked_per_wallet,
+        eta,
+    });
+
+    emit!(ConfigChangeQueued {
+        pool: pool_account.key(),
+        config_manager: ctx.accounts.config_manager.key(),
+        runway_day,
+        emission_per_second,
+        max_staked_nft,
+        max_staked_per_wallet,
+        eta,
+        timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = !pool_account.is_paused(PAUSE_WITHDRAW) @ StakingError::OperationPaused,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_USER_STATE_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_STAKEINFO_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            nft_mint.key().as_ref(),
+        ],
+        bump,
+        constraint = nft_stake_info_account.owner == owner.key() @ StakingError::InvalidUserAddress,
+    )]
+    pub nft_stake_info_account: Account<'info, StakeInfo>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_CLASS_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            nft_stake_info_account.class_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub class_config: Box<Account<'info, ClassConfig>>,
+
+    pub nft_mint: Account<'info, Mint>,
+}
+
+/**
+ * Stop the nft from earning and start its class cooldown, `withdraw_nft` succeeds once it is over.
+ */
+pub fn request_unstake(ctx: Context<RequestUnstake>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let staking_info = &mut ctx.accounts.nft_stake_info_account;
+    ctx.accounts.pool_account.request_unstake(
+        staking_info,
+        &mut ctx.accounts.class_config,
+        &mut ctx.accounts.user_state,
+        timestamp,
+    )?;
+
+    emit!(UnstakeRequested {
+        pool: ctx.accounts.pool_account.key(),
+        user: ctx.accounts.owner.key(),
+        mint: ctx.accounts.nft_mint.key(),
+        class_id: staking_info.class_id,
+        cooldown_end: staking_info.cooldown_end,
+        timestamp,
+    });
+    Ok(())
+}
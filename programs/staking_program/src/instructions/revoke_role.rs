@@ -0,0 +1,35 @@
+use crate::{constants::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = admin,
+        constraint = pool_account.is_initialized == true,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+}
+
+/**
+ * Leave `role` without a holder until it is granted again.
+ */
+pub fn handle(ctx: Context<RevokeRole>, role: Role) -> Result<()> {
+    let pool_account = &mut ctx.accounts.pool_account;
+    let account = pool_account.role_holders[role as usize];
+    pool_account.role_holders[role as usize] = Pubkey::default();
+
+    emit!(RoleRevoked {
+        pool: pool_account.key(),
+        admin: pool_account.admin,
+        role,
+        account,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetClassEnabled<'info> {
+    pub config_manager: Signer<'info>,
+
+    #[account(
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = pool_account.has_role(Role::ConfigManager, &config_manager.key()) @ StakingError::NotAllowedAuthority,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_CLASS_SEED.as_bytes(),
+            pool_account.key().as_ref(),
+            class_config.class_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub class_config: Box<Account<'info, ClassConfig>>,
+}
+
+/**
+ * Disable or re-enable staking into a class. Nfts already staked keep earning and can be withdrawn.
+ */
+pub fn handle(ctx: Context<SetClassEnabled>, enabled: bool) -> Result<()> {
+    let class_config = &mut ctx.accounts.class_config;
+    class_config.enabled = enabled;
+
+    emit!(ClassEnabledChanged {
+        pool: ctx.accounts.pool_account.key(),
+        class_id: class_config.class_id,
+        config_manager: ctx.accounts.config_manager.key(),
+        enabled,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
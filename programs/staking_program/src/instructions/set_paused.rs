@@ -0,0 +1,38 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(mut)]
+    pub pauser: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = pool_account.has_role(Role::Pauser, &pauser.key()) @ StakingError::NotAllowedAuthority,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+}
+
+/**
+ * Set which operations are paused, as a combination of the `PAUSE_*` flags.
+ */
+pub fn handle(ctx: Context<SetPaused>, pause_flags: u8) -> Result<()> {
+    require!(
+        pause_flags & !PAUSE_ALL == 0,
+        StakingError::InvalidPauseFlags
+    );
+    let timestamp = Clock::get()?.unix_timestamp;
+    let pool_account = &mut ctx.accounts.pool_account;
+    pool_account.set_pause_flags(pause_flags, timestamp)?;
+
+    emit!(PauseChanged {
+        pool: pool_account.key(),
+        pauser: ctx.accounts.pauser.key(),
+        pause_flags,
+        timestamp,
+    });
+    Ok(())
+}
@@ -1,40 +1,78 @@
-use crate::{constants::*, states::*};
+use crate::{constants::*, error::*, events::*, states::*, utils::*};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer, Mint};
+use anchor_spl::token::{Token, TokenAccount, Mint};
+use mpl_token_metadata::state::{EDITION, PREFIX as METADATA_PREFIX};
 
 use std::mem::size_of;
 
 #[derive(Accounts)]
-// #[instruction(global_bump: u8, staked_nft_bump: u8)]
+#[instruction(class_id: u32)]
 pub struct StakeNft<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [RS_PREFIX.as_bytes()],
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
         bump,
         constraint = pool_account.is_initialized == true,
-        constraint = pool_account.paused == false,
+        constraint = !pool_account.is_paused(PAUSE_STAKE) @ StakingError::OperationPaused,
     )]
     pub pool_account: Account<'info, PoolConfig>,
 
-    #[account(mut)]
-    pub user_nft_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [
+            RS_CLASS_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            class_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub class_config: Box<Account<'info, ClassConfig>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [
+            RS_USER_STATE_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        space = 8 + size_of::<UserState>(),
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        mut,
+        constraint = user_nft_token_account.mint == nft_mint.key() @ StakingError::InvalidNFTAddress,
+        constraint = user_nft_token_account.owner == owner.key() @ StakingError::InvalidOwner,
+        constraint = user_nft_token_account.amount == 1 @ StakingError::InvalidNftAmount,
+    )]
+    pub user_nft_token_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = owner,
-        seeds = [RS_STAKE_SEED.as_ref(), nft_mint.key.as_ref()],
+        seeds = [
+            RS_STAKE_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            nft_mint.key().as_ref(),
+        ],
         bump,
         token::mint = nft_mint,
         token::authority = pool_account,
     )]
-    pub dest_nft_token_account: Account<'info, TokenAccount>,
+    pub dest_nft_token_account: Box<Account<'info, TokenAccount>>,
     #[account(
         init,
         payer = owner,
-        seeds = [RS_STAKEINFO_SEED.as_ref(), nft_mint.key.as_ref()],
+        seeds = [
+            RS_STAKEINFO_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            nft_mint.key().as_ref(),
+        ],
         bump,
         space = 8 + size_of::<StakeInfo>(),
     )]
@@ -42,13 +80,55 @@ pub struct StakeNft<'info> {
 
     pub nft_mint: Account<'info, Mint>,
 
+    /// CHECK: address is the Metaplex metadata PDA of `nft_mint`, contents are parsed in `validate`
+    #[account(
+        seeds = [
+            METADATA_PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            nft_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = mpl_token_metadata::id(),
+    )]
+    pub nft_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: address is the Metaplex master edition PDA of `nft_mint`, used to freeze in `Freeze` mode
+    #[account(
+        seeds = [
+            METADATA_PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            nft_mint.key().as_ref(),
+            EDITION.as_bytes(),
+        ],
+        bump,
+        seeds::program = mpl_token_metadata::id(),
+    )]
+    pub nft_edition: UncheckedAccount<'info>,
+
+    /// CHECK: address is checked against the Metaplex token metadata program id
+    #[account(address = mpl_token_metadata::id())]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
     pub token_program: Program<'info, Token>,
     // pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn stake_nft(ctx: Context<StakeNft>, class_id: u32) -> Result<()> {
+impl<'info> StakeNft<'info> {
+    pub fn validate(&self, class_id: u32, proof: &[[u8; 32]]) -> Result<()> {
+        verify_nft(
+            &self.pool_account,
+            &self.nft_mint.key(),
+            &self.nft_metadata,
+            class_id,
+            proof,
+        )
+    }
+}
+
+#[access_control(ctx.accounts.validate(class_id, &proof))]
+pub fn stake_nft(ctx: Context<StakeNft>, class_id: u32, proof: Vec<[u8; 32]>) -> Result<()> {
     let timestamp = Clock::get()?.unix_timestamp;
 
     // set stake info
@@ -59,17 +139,43 @@ pub fn stake_nft(ctx: Context<StakeNft>, class_id: u32) -> Result<()> {
     staking_info.last_update_time = timestamp;
     staking_info.class_id = class_id;
 
+    let user_state = &mut ctx.accounts.user_state;
+    user_state.pool = ctx.accounts.pool_account.key();
+    user_state.owner = ctx.accounts.owner.key();
+
     // set global info
-    ctx.accounts.pool_account.staked_nft += 1;
-
-    // transfer nft to pda (don't need to transfer in this project)
-    // let cpi_accounts = Transfer {
-    //     from: ctx.accounts.user_nft_token_account.to_account_info(),
-    //     to: ctx.accounts.dest_nft_token_account.to_account_info(),
-    //     authority: ctx.accounts.owner.to_account_info(),
-    // };
-    // let token_program = ctx.accounts.token_program.to_account_info();
-    // let transfer_ctx = CpiContext::new(token_program, cpi_accounts);
-    // token::transfer(transfer_ctx, 1)?;
+    let pool_account = &mut ctx.accounts.pool_account;
+    pool_account.update_pool(timestamp)?;
+    pool_account.add_stake(
+        staking_info,
+        &mut ctx.accounts.class_config,
+        user_state,
+        timestamp,
+    )?;
+
+    let pool_id = pool_account.pool_id.to_le_bytes();
+    let (_pool_account_seed, _bump) =
+        Pubkey::find_program_address(&[RS_PREFIX.as_bytes(), &pool_id], ctx.program_id);
+    let seeds = &[RS_PREFIX.as_bytes(), &pool_id, &[_bump]];
+
+    let custody = NftCustody {
+        owner: ctx.accounts.owner.to_account_info(),
+        pool_account: ctx.accounts.pool_account.to_account_info(),
+        nft_mint: ctx.accounts.nft_mint.to_account_info(),
+        nft_edition: ctx.accounts.nft_edition.to_account_info(),
+        user_nft_token_account: ctx.accounts.user_nft_token_account.to_account_info(),
+        staked_nft_token_account: ctx.accounts.dest_nft_token_account.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+    };
+    custody.lock(ctx.accounts.pool_account.custody_mode, &[&seeds[..]])?;
+
+    emit!(NftStaked {
+        pool: ctx.accounts.pool_account.key(),
+        user: ctx.accounts.owner.key(),
+        mint: ctx.accounts.nft_mint.key(),
+        class_id,
+        timestamp,
+    });
     Ok(())
 }
@@ -0,0 +1,213 @@
+use crate::{constants::*, error::*, events::*, states::*, utils::*};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, InitializeAccount, Mint, Token, TokenAccount};
+
+use std::mem::size_of;
+
+// remaining accounts per nft: nft_mint, nft_metadata, nft_edition, nft_stake_info_account (mut),
+// user_nft_token_account (mut), dest_nft_token_account (mut), class_config (mut)
+const STAKE_MANY_ACCOUNTS: usize = 7;
+
+#[derive(Accounts)]
+pub struct StakeMany<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = !pool_account.is_paused(PAUSE_STAKE) @ StakingError::OperationPaused,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [
+            RS_USER_STATE_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        space = 8 + size_of::<UserState>(),
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    /// CHECK: address is checked against the Metaplex token metadata program id
+    #[account(address = mpl_token_metadata::id())]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/**
+ * Stake several nfts at once, `class_ids` and `proofs` are given in the order of the nfts.
+ */
+pub fn stake_many<'info>(
+    ctx: Context<'_, '_, '_, 'info, StakeMany<'info>>,
+    class_ids: Vec<u32>,
+    proofs: Vec<Vec<[u8; 32]>>,
+) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let remaining_accounts = ctx.remaining_accounts;
+    require!(
+        !class_ids.is_empty()
+            && class_ids.len() <= MAX_STAKE_BATCH
+            && proofs.len() == class_ids.len()
+            && remaining_accounts.len() == class_ids.len() * STAKE_MANY_ACCOUNTS,
+        StakingError::InvalidBatchSize
+    );
+
+    let owner = ctx.accounts.owner.key();
+    let pool_key = ctx.accounts.pool_account.key();
+    let pool_info = ctx.accounts.pool_account.to_account_info();
+    let pool_account = &mut ctx.accounts.pool_account;
+    let custody_mode = pool_account.custody_mode;
+    let rent = &ctx.accounts.rent;
+
+    let pool_id = pool_account.pool_id.to_le_bytes();
+    let (_pool_account_seed, _bump) =
+        Pubkey::find_program_address(&[RS_PREFIX.as_bytes(), &pool_id], ctx.program_id);
+    let seeds = &[RS_PREFIX.as_bytes(), &pool_id, &[_bump]];
+
+    let user_state = &mut ctx.accounts.user_state;
+    user_state.pool = pool_key;
+    user_state.owner = owner;
+
+    let mut classes = ClassCache::default();
+    for (i, accounts) in remaining_accounts.chunks(STAKE_MANY_ACCOUNTS).enumerate() {
+        let nft_mint = Account::<Mint>::try_from(&accounts[0])?;
+        verify_nft(
+            pool_account,
+            &nft_mint.key(),
+            &accounts[1],
+            class_ids[i],
+            &proofs[i],
+        )?;
+
+        let user_nft_token_account = Account::<TokenAccount>::try_from(&accounts[4])?;
+        require!(
+            user_nft_token_account.mint == nft_mint.key(),
+            StakingError::InvalidNFTAddress
+        );
+        require!(
+            user_nft_token_account.owner == owner,
+            StakingError::InvalidOwner
+        );
+        require!(
+            user_nft_token_account.amount == 1,
+            StakingError::InvalidNftAmount
+        );
+
+        // create the stake info pda, it fails if the nft is already staked
+        let stake_info = &accounts[3];
+        let (stake_info_key, stake_info_bump) = Pubkey::find_program_address(
+            &[
+                RS_STAKEINFO_SEED.as_ref(),
+                pool_key.as_ref(),
+                nft_mint.key().as_ref(),
+            ],
+            ctx.program_id,
+        );
+        require!(
+            stake_info_key == stake_info.key(),
+            StakingError::InvalidNFTAddress
+        );
+        create_pda_account(
+            &ctx.accounts.owner.to_account_info(),
+            stake_info,
+            &ctx.accounts.system_program.to_account_info(),
+            rent,
+            8 + size_of::<StakeInfo>(),
+            ctx.program_id,
+            &[
+                RS_STAKEINFO_SEED.as_ref(),
+                pool_key.as_ref(),
+                nft_mint.key().as_ref(),
+                &[stake_info_bump],
+            ],
+        )?;
+
+        // create the escrow token account unless it already exists
+        let dest_nft_token_account = &accounts[5];
+        let (dest_key, dest_bump) = Pubkey::find_program_address(
+            &[
+                RS_STAKE_SEED.as_ref(),
+                pool_key.as_ref(),
+                nft_mint.key().as_ref(),
+            ],
+            ctx.program_id,
+        );
+        require!(
+            dest_key == dest_nft_token_account.key(),
+            StakingError::InvalidNFTAddress
+        );
+        if dest_nft_token_account.data_is_empty() {
+            create_pda_account(
+                &ctx.accounts.owner.to_account_info(),
+                dest_nft_token_account,
+                &ctx.accounts.system_program.to_account_info(),
+                rent,
+                TokenAccount::LEN,
+                &token::ID,
+                &[
+                    RS_STAKE_SEED.as_ref(),
+                    pool_key.as_ref(),
+                    nft_mint.key().as_ref(),
+                    &[dest_bump],
+                ],
+            )?;
+            token::initialize_account(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                InitializeAccount {
+                    account: dest_nft_token_account.clone(),
+                    mint: nft_mint.to_account_info(),
+                    authority: pool_info.clone(),
+                    rent: rent.to_account_info(),
+                },
+            ))?;
+        }
+
+        // set stake info
+        let mut staking_info = StakeInfo {
+            class_id: class_ids[i],
+            owner,
+            nft_addr: nft_mint.key(),
+            stake_time: timestamp,
+            last_update_time: timestamp,
+            ..Default::default()
+        };
+
+        // set global info
+        pool_account.update_pool(timestamp)?;
+        let class = classes.load(&accounts[6], &pool_key, class_ids[i])?;
+        pool_account.add_stake(&mut staking_info, class, user_state, timestamp)?;
+        staking_info.try_serialize(&mut &mut stake_info.try_borrow_mut_data()?[..])?;
+
+        let custody = NftCustody {
+            owner: ctx.accounts.owner.to_account_info(),
+            pool_account: pool_info.clone(),
+            nft_mint: nft_mint.to_account_info(),
+            nft_edition: accounts[2].clone(),
+            user_nft_token_account: accounts[4].clone(),
+            staked_nft_token_account: dest_nft_token_account.clone(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+        };
+        custody.lock(custody_mode, &[&seeds[..]])?;
+
+        emit!(NftStaked {
+            pool: pool_key,
+            user: owner,
+            mint: nft_mint.key(),
+            class_id: class_ids[i],
+            timestamp,
+        });
+    }
+    classes.exit(ctx.program_id)?;
+    Ok(())
+}
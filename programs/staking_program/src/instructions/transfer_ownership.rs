@@ -1,23 +1,29 @@
-use crate::{constants::*, states::*};
+use crate::{constants::*, events::*, states::*};
 use anchor_lang::prelude::*;
 
+// only run through `execute_proposal`, the approved proposal authorizes it,
+// so a lost admin key can still be replaced
 #[derive(Accounts)]
 pub struct TransferOwnership<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
-
     #[account(
         mut,
-        seeds = [RS_PREFIX.as_bytes()],
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
         bump,
-        has_one = admin,
         constraint = pool_account.is_initialized == true,
     )]
     pub pool_account: Account<'info, PoolConfig>,
 }
 
 pub fn handle(ctx: Context<TransferOwnership>, new_admin: Pubkey) -> Result<()> {
+    // the new admin takes over only once it signs `accept_ownership`
     let pool_account = &mut ctx.accounts.pool_account;
-    pool_account.admin = new_admin;
+    pool_account.pending_admin = new_admin;
+
+    emit!(OwnershipTransferStarted {
+        pool: pool_account.key(),
+        admin: pool_account.admin,
+        pending_admin: new_admin,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
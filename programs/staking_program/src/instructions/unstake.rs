@@ -1,9 +1,10 @@
-use crate::{constants::*, error::*, states::*};
+use crate::{constants::*, error::*, events::*, states::*, utils::*};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount, Transfer},
+    token::{Mint, Token, TokenAccount},
 };
+use mpl_token_metadata::state::{EDITION, PREFIX as METADATA_PREFIX};
 
 #[derive(Accounts)]
 pub struct WithdrawNft<'info> {
@@ -12,15 +13,27 @@ pub struct WithdrawNft<'info> {
 
     #[account(
         mut,
-        seeds = [RS_PREFIX.as_bytes()],
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
         bump,
         constraint = pool_account.is_initialized == true,
-        constraint = pool_account.paused == false,
+        constraint = !pool_account.is_paused(PAUSE_WITHDRAW) @ StakingError::OperationPaused,
     )]
     pub pool_account: Account<'info, PoolConfig>,
 
     #[account(
         mut,
+        seeds = [
+            RS_USER_STATE_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        mut,
+        address = pool_account.reward_vault,
         token::mint = reward_mint,
         token::authority = pool_account,
     )]
@@ -31,7 +44,11 @@ pub struct WithdrawNft<'info> {
 
     #[account(
         mut,
-        seeds = [RS_STAKEINFO_SEED.as_ref(), nft_mint.key().as_ref()],
+        seeds = [
+            RS_STAKEINFO_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            nft_mint.key().as_ref(),
+        ],
         bump,
         close = owner,
     )]
@@ -39,16 +56,36 @@ pub struct WithdrawNft<'info> {
 
     #[account(
         mut,
-        // constraint = user_nft_token_account.owner == owner.key()
+        seeds = [
+            RS_CLASS_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            nft_stake_info_account.class_id.to_le_bytes().as_ref(),
+        ],
+        bump,
     )]
-    pub user_nft_token_account: Account<'info, TokenAccount>,
+    pub class_config: Box<Account<'info, ClassConfig>>,
 
     #[account(
         mut,
-        seeds = [RS_STAKE_SEED.as_ref(), nft_mint.key().as_ref()],
+        constraint = user_nft_token_account.mint == nft_mint.key() @ StakingError::InvalidNFTAddress,
+        constraint = user_nft_token_account.owner == owner.key() @ StakingError::InvalidOwner,
+    )]
+    pub user_nft_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_STAKE_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            nft_mint.key().as_ref(),
+        ],
         bump,
+        token::mint = nft_mint,
+        token::authority = pool_account,
+        constraint = pool_account.custody_mode != CustodyMode::Escrow
+            || staked_nft_token_account.amount == 1 @ StakingError::InvalidNftAmount,
     )]
-    pub staked_nft_token_account: Account<'info, TokenAccount>,
+    pub staked_nft_token_account: Box<Account<'info, TokenAccount>>,
 
     // send reward to user reward vault
     #[account(
@@ -59,9 +96,31 @@ pub struct WithdrawNft<'info> {
     )]
     reward_to_account: Box<Account<'info, TokenAccount>>,
 
+    /// CHECK: receives early-unstake penalties, the reward vault itself when they stay in the pool.
+    /// Only used by the token transfer of a penalty, so a closed treasury cannot block withdrawals.
+    #[account(mut, address = pool_account.penalty_treasury)]
+    pub penalty_treasury: UncheckedAccount<'info>,
+
     /// CHECK: "nft_mint" is unsafe, but is not documented.
     pub nft_mint: Account<'info, Mint>,
 
+    /// CHECK: address is the Metaplex master edition PDA of `nft_mint`, used to thaw in `Freeze` mode
+    #[account(
+        seeds = [
+            METADATA_PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            nft_mint.key().as_ref(),
+            EDITION.as_bytes(),
+        ],
+        bump,
+        seeds::program = mpl_token_metadata::id(),
+    )]
+    pub nft_edition: UncheckedAccount<'info>,
+
+    /// CHECK: address is checked against the Metaplex token metadata program id
+    #[account(address = mpl_token_metadata::id())]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
@@ -83,39 +142,41 @@ pub fn withdraw_nft(ctx: Context<WithdrawNft>) -> Result<()> {
     let timestamp = Clock::get()?.unix_timestamp;
     let staking_info = &mut ctx.accounts.nft_stake_info_account;
     let pool_account = &mut ctx.accounts.pool_account;
+    let user_state = &mut ctx.accounts.user_state;
 
-    let lock_day = pool_account.lock_day_by_class[staking_info.class_id as usize];
-    let unlock_time = staking_info
-        .stake_time
-        .checked_add((lock_day as i64).checked_mul(86400 as i64).unwrap())
-        .unwrap();
-
-    require!((unlock_time < timestamp), StakingError::InvalidWithdrawTime);
-
-    let reward_per_day = pool_account.reward_policy_by_class[staking_info.class_id as usize];
     // When withdraw nft, calculate and send reward SWRD
-    let mut reward: u64 = staking_info.update_reward(timestamp, reward_per_day)?;
-
+    // the nft is always returned, reward the vault cannot pay is owed to the wallet
     let vault_balance = ctx.accounts.reward_vault.amount;
-    if vault_balance < reward {
-        reward = vault_balance;
-    }
-
-    ctx.accounts.pool_account.staked_nft -= 1;
+    let (reward, penalty) = pool_account.unstake(
+        staking_info,
+        &mut ctx.accounts.class_config,
+        user_state,
+        timestamp,
+        vault_balance,
+    )?;
+    // stakers are paid first, the part of the penalty the vault cannot cover is dropped
+    let penalty_transfer = penalty.min(vault_balance - reward);
+
+    user_state.record_claim(reward, timestamp)?;
 
     // get pool_account seed
+    let pool_id = pool_account.pool_id.to_le_bytes();
     let (_pool_account_seed, _pool_account_bump) =
-        Pubkey::find_program_address(&[&(RS_PREFIX.as_bytes())], ctx.program_id);
-    let seeds = &[RS_PREFIX.as_bytes(), &[_pool_account_bump]];
+        Pubkey::find_program_address(&[RS_PREFIX.as_bytes(), &pool_id], ctx.program_id);
+    let seeds = &[RS_PREFIX.as_bytes(), &pool_id, &[_pool_account_bump]];
     let signer = &[&seeds[..]];
-    // let cpi_accounts = Transfer {
-    //     from: ctx.accounts.staked_nft_token_account.to_account_info(),
-    //     to: ctx.accounts.user_nft_token_account.to_account_info(),
-    //     authority: ctx.accounts.pool_account.to_account_info(),
-    // };
-    // let token_program = ctx.accounts.token_program.to_account_info().clone();
-    // let transfer_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer);
-    // token::transfer(transfer_ctx, 1)?;
+
+    let custody = NftCustody {
+        owner: ctx.accounts.owner.to_account_info(),
+        pool_account: ctx.accounts.pool_account.to_account_info(),
+        nft_mint: ctx.accounts.nft_mint.to_account_info(),
+        nft_edition: ctx.accounts.nft_edition.to_account_info(),
+        user_nft_token_account: ctx.accounts.user_nft_token_account.to_account_info(),
+        staked_nft_token_account: ctx.accounts.staked_nft_token_account.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+    };
+    custody.release(ctx.accounts.pool_account.custody_mode, signer)?;
 
     if reward > 0 {
         let token_accounts = anchor_spl::token::Transfer {
@@ -133,5 +194,30 @@ pub fn withdraw_nft(ctx: Context<WithdrawNft>) -> Result<()> {
         );
         anchor_spl::token::transfer(cpi_ctx.with_signer(signer), reward)?;
     }
+
+    if penalty_transfer > 0 && ctx.accounts.pool_account.has_penalty_treasury() {
+        let token_accounts = anchor_spl::token::Transfer {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            to: ctx.accounts.penalty_treasury.to_account_info(),
+            authority: ctx.accounts.pool_account.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), token_accounts);
+        msg!(
+            "Calling the token program to transfer penalty {} to the treasury",
+            penalty_transfer
+        );
+        anchor_spl::token::transfer(cpi_ctx.with_signer(signer), penalty_transfer)?;
+    }
+
+    emit!(NftWithdrawn {
+        pool: ctx.accounts.pool_account.key(),
+        user: ctx.accounts.owner.key(),
+        mint: ctx.accounts.nft_mint.key(),
+        class_id: ctx.accounts.nft_stake_info_account.class_id,
+        reward,
+        penalty,
+        unpaid_reward: ctx.accounts.user_state.unpaid_reward,
+        timestamp,
+    });
     Ok(())
 }
@@ -0,0 +1,39 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ChangeClassRoot<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = admin,
+        constraint = pool_account.is_initialized == true,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+}
+
+/**
+ * Queue a class root that can be executed once `config_delay` has passed.
+ * A root already in the queue is replaced and its delay starts over.
+ */
+pub fn handle(ctx: Context<ChangeClassRoot>, class_root: [u8; 32]) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let pool_account = &mut ctx.accounts.pool_account;
+    let eta = timestamp
+        .checked_add(pool_account.config_delay)
+        .ok_or(StakingError::MathOverflow)?;
+    pool_account.queued_class_root = Some(QueuedClassRoot { class_root, eta });
+
+    emit!(ClassRootChangeQueued {
+        pool: pool_account.key(),
+        admin: ctx.accounts.admin.key(),
+        class_root,
+        eta,
+        timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ChangeConfigDelay<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = admin,
+        constraint = pool_account.is_initialized == true,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+}
+
+pub fn handle(ctx: Context<ChangeConfigDelay>, config_delay: i64) -> Result<()> {
+    let pool_account = &mut ctx.accounts.pool_account;
+    // stakers rely on the delay, so it never gets shorter
+    require!(
+        config_delay >= pool_account.config_delay && config_delay <= MAX_CONFIG_DELAY,
+        StakingError::InvalidConfigDelay
+    );
+    pool_account.config_delay = config_delay;
+
+    emit!(ConfigDelayChanged {
+        pool: pool_account.key(),
+        admin: ctx.accounts.admin.key(),
+        config_delay,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
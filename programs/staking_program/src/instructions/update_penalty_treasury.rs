@@ -0,0 +1,36 @@
+use crate::{constants::*, error::*, events::*, states::*};
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+#[derive(Accounts)]
+pub struct ChangePenaltyTreasury<'info> {
+    pub treasurer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = pool_account.has_role(Role::Treasurer, &treasurer.key()) @ StakingError::NotAllowedAuthority,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    // pass the reward vault to keep penalties in the pool
+    #[account(
+        constraint = penalty_treasury.mint == pool_account.reward_mint @ StakingError::InvalidTreasury,
+    )]
+    pub penalty_treasury: Box<Account<'info, TokenAccount>>,
+}
+
+pub fn handle(ctx: Context<ChangePenaltyTreasury>) -> Result<()> {
+    let pool_account = &mut ctx.accounts.pool_account;
+    pool_account.penalty_treasury = ctx.accounts.penalty_treasury.key();
+
+    emit!(PenaltyTreasuryChanged {
+        pool: pool_account.key(),
+        treasurer: ctx.accounts.treasurer.key(),
+        penalty_treasury: pool_account.penalty_treasury,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
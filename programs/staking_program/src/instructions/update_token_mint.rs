@@ -1,23 +1,160 @@
-use crate::{constants::*, states::*};
+use crate::{constants::*, events::*, states::*};
 use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer},
+};
 
+// only run through `execute_proposal`, the approved proposal authorizes it
 #[derive(Accounts)]
 pub struct ChangeRewardMint<'info> {
+    // pays for the new vault and token account and funds the new vault, usually the proposal executor
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub payer: Signer<'info>,
+
+    /// CHECK: receives the old vault balance and rent, must be the treasurer named in the proposal
+    #[account(mut)]
+    pub treasurer: UncheckedAccount<'info>,
 
     #[account(
         mut,
-        seeds = [RS_PREFIX.as_bytes()],
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
         bump,
-        has_one = admin,
         constraint = pool_account.is_initialized == true,
     )]
     pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(address = pool_account.reward_mint)]
+    pub old_reward_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        address = pool_account.reward_vault,
+        token::mint = old_reward_mint,
+        token::authority = pool_account,
+    )]
+    pub old_reward_vault: Box<Account<'info, TokenAccount>>,
+
+    // receives the balance left in the old vault
+    #[account(
+      init_if_needed,
+      payer = payer,
+      associated_token::mint = old_reward_mint,
+      associated_token::authority = treasurer
+    )]
+    pub old_reward_to_account: Box<Account<'info, TokenAccount>>,
+
+    pub new_reward_mint: Box<Account<'info, Mint>>,
+
+    // reward vault that holds the new reward mint for distribution
+    #[account(
+        init,
+        token::mint = new_reward_mint,
+        token::authority = pool_account,
+        seeds = [
+            RS_VAULT_SEED.as_bytes(),
+            pool_account.key().as_ref(),
+            new_reward_mint.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+    )]
+    pub new_reward_vault: Box<Account<'info, TokenAccount>>,
+
+    // funds the new vault with the reserved reward
+    #[account(
+        mut,
+        token::mint = new_reward_mint,
+        token::authority = payer,
+    )]
+    pub funding_account: Box<Account<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn handle(ctx: Context<ChangeRewardMint>, reward_mint: Pubkey) -> Result<()> {
+/**
+ * Move the pool to a new reward mint and vault.
+ * The payer funds the new vault with the reserved reward in the new units, so rewards
+ * accrued so far are paid out of it. The old vault balance then goes back to the treasurer
+ * and the old vault is closed.
+ */
+pub fn handle(ctx: Context<ChangeRewardMint>) -> Result<()> {
+    // accrue in the old units before they change
     let pool_account = &mut ctx.accounts.pool_account;
-    pool_account.reward_mint = reward_mint;
+    pool_account.update_pool(Clock::get()?.unix_timestamp)?;
+    pool_account.set_reward_decimals(ctx.accounts.new_reward_mint.decimals)?;
+    let funded_amount = pool_account.reserved_reward()?;
+
+    if funded_amount > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funding_account.to_account_info(),
+            to: ctx.accounts.new_reward_vault.to_account_info(),
+            authority: ctx.accounts.payer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        msg!(
+            "Calling the token program to fund the new vault with reward {}",
+            funded_amount
+        );
+        token::transfer(cpi_ctx, funded_amount)?;
+    }
+
+    let pool_id = ctx.accounts.pool_account.pool_id.to_le_bytes();
+    let (_pool_account_seed, _bump) =
+        Pubkey::find_program_address(&[RS_PREFIX.as_bytes(), &pool_id], ctx.program_id);
+    let pool_seeds = &[RS_PREFIX.as_bytes(), &pool_id, &[_bump]];
+    let signer = &[&pool_seeds[..]];
+
+    let returned_amount = ctx.accounts.old_reward_vault.amount;
+    if returned_amount > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.old_reward_vault.to_account_info(),
+            to: ctx.accounts.old_reward_to_account.to_account_info(),
+            authority: ctx.accounts.pool_account.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        msg!(
+            "Calling the token program to return reward {} to the treasurer",
+            returned_amount
+        );
+        token::transfer(cpi_ctx, returned_amount)?;
+    }
+
+    // close the old vault to refund its rent to the treasurer
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.old_reward_vault.to_account_info(),
+        destination: ctx.accounts.treasurer.to_account_info(),
+        authority: ctx.accounts.pool_account.to_account_info(),
+    };
+    let close_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token::close_account(close_ctx)?;
+
+    let pool_account = &mut ctx.accounts.pool_account;
+    let old_mint = pool_account.reward_mint;
+    pool_account.reward_mint = ctx.accounts.new_reward_mint.key();
+    pool_account.reward_vault = ctx.accounts.new_reward_vault.key();
+    // a treasury of the old mint cannot take penalties anymore
+    pool_account.penalty_treasury = pool_account.reward_vault;
+
+    emit!(RewardMintChanged {
+        pool: pool_account.key(),
+        treasurer: ctx.accounts.treasurer.key(),
+        old_mint,
+        new_mint: pool_account.reward_mint,
+        funded_amount,
+        returned_amount,
+        timestamp: pool_account.last_update_time,
+    });
     Ok(())
 }
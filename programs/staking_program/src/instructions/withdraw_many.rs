@@ -0,0 +1,227 @@
+use crate::{constants::*, error::*, events::*, states::*, utils::*};
+use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+// remaining accounts per nft: nft_mint, nft_edition, nft_stake_info_account (mut),
+// user_nft_token_account (mut), staked_nft_token_account (mut), class_config (mut)
+const WITHDRAW_MANY_ACCOUNTS: usize = 6;
+
+#[derive(Accounts)]
+pub struct WithdrawMany<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = pool_account.is_initialized == true,
+        constraint = !pool_account.is_paused(PAUSE_WITHDRAW) @ StakingError::OperationPaused,
+    )]
+    pub pool_account: Account<'info, PoolConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            RS_USER_STATE_SEED.as_ref(),
+            pool_account.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
+    #[account(
+        mut,
+        address = pool_account.reward_vault,
+        token::mint = reward_mint,
+        token::authority = pool_account,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = pool_account.reward_mint)]
+    pub reward_mint: Account<'info, Mint>,
+
+    // send reward to user reward vault
+    #[account(
+      init_if_needed,
+      payer = owner,
+      associated_token::mint = reward_mint,
+      associated_token::authority = owner
+    )]
+    reward_to_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: receives early-unstake penalties, the reward vault itself when they stay in the pool.
+    /// Only used by the token transfer of a penalty, so a closed treasury cannot block withdrawals.
+    #[account(mut, address = pool_account.penalty_treasury)]
+    pub penalty_treasury: UncheckedAccount<'info>,
+
+    /// CHECK: address is checked against the Metaplex token metadata program id
+    #[account(address = mpl_token_metadata::id())]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/**
+ * Withdraw several staked nfts and pay their combined reward with a single transfer.
+ */
+pub fn withdraw_many<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawMany<'info>>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let remaining_accounts = ctx.remaining_accounts;
+    let count = remaining_accounts.len() / WITHDRAW_MANY_ACCOUNTS;
+    require!(
+        count > 0
+            && count <= MAX_WITHDRAW_BATCH
+            && remaining_accounts.len() == count * WITHDRAW_MANY_ACCOUNTS,
+        StakingError::InvalidBatchSize
+    );
+
+    let owner = ctx.accounts.owner.key();
+    let pool_key = ctx.accounts.pool_account.key();
+    let pool_info = ctx.accounts.pool_account.to_account_info();
+    let pool_account = &mut ctx.accounts.pool_account;
+    let user_state = &mut ctx.accounts.user_state;
+    let custody_mode = pool_account.custody_mode;
+    let mut vault_balance = ctx.accounts.reward_vault.amount;
+    let mut reward: u64 = 0;
+    let mut penalty_transfer: u64 = 0;
+    let has_penalty_treasury = pool_account.has_penalty_treasury();
+
+    // get pool_account seed
+    let pool_id = pool_account.pool_id.to_le_bytes();
+    let (_pool_account_seed, _pool_account_bump) =
+        Pubkey::find_program_address(&[RS_PREFIX.as_bytes(), &pool_id], ctx.program_id);
+    let seeds = &[RS_PREFIX.as_bytes(), &pool_id, &[_pool_account_bump]];
+    let signer = &[&seeds[..]];
+
+    let mut classes = ClassCache::default();
+    for accounts in remaining_accounts.chunks(WITHDRAW_MANY_ACCOUNTS) {
+        let nft_mint = &accounts[0];
+        let mut staking_info = Account::<StakeInfo>::try_from(&accounts[2])?;
+
+        let (stake_info_key, _bump) = Pubkey::find_program_address(
+            &[
+                RS_STAKEINFO_SEED.as_ref(),
+                pool_key.as_ref(),
+                nft_mint.key().as_ref(),
+            ],
+            ctx.program_id,
+        );
+        require!(
+            stake_info_key == staking_info.key(),
+            StakingError::InvalidNFTAddress
+        );
+        require!(
+            staking_info.owner == owner,
+            StakingError::InvalidUserAddress
+        );
+
+        let user_nft_token_account = Account::<TokenAccount>::try_from(&accounts[3])?;
+        require!(
+            user_nft_token_account.mint == nft_mint.key(),
+            StakingError::InvalidNFTAddress
+        );
+        require!(
+            user_nft_token_account.owner == owner,
+            StakingError::InvalidOwner
+        );
+
+        let (staked_nft_key, _bump) = Pubkey::find_program_address(
+            &[
+                RS_STAKE_SEED.as_ref(),
+                pool_key.as_ref(),
+                nft_mint.key().as_ref(),
+            ],
+            ctx.program_id,
+        );
+        require!(
+            staked_nft_key == accounts[4].key(),
+            StakingError::InvalidNFTAddress
+        );
+
+        // the nft is always returned, reward the vault cannot pay is owed to the wallet
+        let class = classes.load(&accounts[5], &pool_key, staking_info.class_id)?;
+        let (nft_reward, nft_penalty) = pool_account.unstake(
+            &mut staking_info,
+            class,
+            user_state,
+            timestamp,
+            vault_balance,
+        )?;
+        vault_balance -= nft_reward;
+        if has_penalty_treasury {
+            // stakers are paid first, the part of the penalty the vault cannot cover is dropped
+            let nft_penalty_transfer = nft_penalty.min(vault_balance);
+            vault_balance -= nft_penalty_transfer;
+            penalty_transfer += nft_penalty_transfer;
+        }
+        reward = reward
+            .checked_add(nft_reward)
+            .ok_or(StakingError::MathOverflow)?;
+
+        let custody = NftCustody {
+            owner: ctx.accounts.owner.to_account_info(),
+            pool_account: pool_info.clone(),
+            nft_mint: nft_mint.clone(),
+            nft_edition: accounts[1].clone(),
+            user_nft_token_account: accounts[3].clone(),
+            staked_nft_token_account: accounts[4].clone(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+        };
+        custody.release(custody_mode, signer)?;
+        staking_info.close(ctx.accounts.owner.to_account_info())?;
+
+        emit!(NftWithdrawn {
+            pool: pool_key,
+            user: owner,
+            mint: nft_mint.key(),
+            class_id: staking_info.class_id,
+            reward: nft_reward,
+            penalty: nft_penalty,
+            unpaid_reward: user_state.unpaid_reward,
+            timestamp,
+        });
+    }
+
+    classes.exit(ctx.program_id)?;
+
+    user_state.record_claim(reward, timestamp)?;
+
+    if reward > 0 {
+        let token_accounts = anchor_spl::token::Transfer {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            to: ctx.accounts.reward_to_account.to_account_info(),
+            authority: pool_info.clone(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), token_accounts);
+        msg!(
+            "Calling the token program to transfer reward {} to the user",
+            reward
+        );
+        anchor_spl::token::transfer(cpi_ctx.with_signer(signer), reward)?;
+    }
+
+    if penalty_transfer > 0 {
+        let token_accounts = anchor_spl::token::Transfer {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            to: ctx.accounts.penalty_treasury.to_account_info(),
+            authority: pool_info,
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), token_accounts);
+        msg!(
+            "Calling the token program to transfer penalty {} to the treasury",
+            penalty_transfer
+        );
+        anchor_spl::token::transfer(cpi_ctx.with_signer(signer), penalty_transfer)?;
+    }
+    Ok(())
+}
@@ -1,25 +1,35 @@
-use crate::{constants::*, states::*};
+use crate::{constants::*, error::*, events::*, states::*};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token::{Mint, Token, TokenAccount},
 };
 
+// only run through `execute_proposal`, the approved proposal authorizes it
 #[derive(Accounts)]
 pub struct WithdrawSwrd<'info> {
+    // pays for the treasurer's token account, usually the proposal executor
     #[account(mut)]
-    admin: Signer<'info>,
+    pub payer: Signer<'info>,
+
+    /// CHECK: receives the reward, must be the treasurer named in the proposal
+    pub treasurer: UncheckedAccount<'info>,
+
     #[account(
         mut,
-        seeds = [RS_PREFIX.as_bytes()],
+        seeds = [RS_PREFIX.as_bytes(), pool_account.pool_id.to_le_bytes().as_ref()],
         bump,
-        has_one = admin,
+        constraint = pool_account.is_initialized == true,
     )]
     pub pool_account: Account<'info, PoolConfig>,
 
     #[account(
         mut,
-        seeds = [ RS_VAULT_SEED.as_bytes(), reward_mint.key().as_ref() ],
+        seeds = [
+            RS_VAULT_SEED.as_bytes(),
+            pool_account.key().as_ref(),
+            reward_mint.key().as_ref(),
+        ],
         bump,
         token::mint = reward_mint,
         token::authority = pool_account,
@@ -29,9 +39,9 @@ pub struct WithdrawSwrd<'info> {
     // send reward to user reward vault
     #[account(
       init_if_needed,
-      payer = admin,
+      payer = payer,
       associated_token::mint = reward_mint,
-      associated_token::authority = admin
+      associated_token::authority = treasurer
     )]
     reward_to_account: Box<Account<'info, TokenAccount>>,
 
@@ -46,15 +56,22 @@ pub struct WithdrawSwrd<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn handle(ctx: Context<WithdrawSwrd>) -> Result<()> {
+pub fn handle(ctx: Context<WithdrawSwrd>, amount: u64) -> Result<()> {
     let vault_amount = ctx.accounts.reward_vault.amount;
 
-    if vault_amount > 0 {
+    // only the surplus above owed rewards and the runway can leave the vault
+    let pool_account = &mut ctx.accounts.pool_account;
+    pool_account.update_pool(Clock::get()?.unix_timestamp)?;
+    let surplus = vault_amount.saturating_sub(pool_account.reserved_reward()?);
+    require!(amount <= surplus, StakingError::InsufficientSurplus);
+
+    if amount > 0 {
+        let pool_id = ctx.accounts.pool_account.pool_id.to_le_bytes();
         let (_pool_account_seed, _bump) =
-            Pubkey::find_program_address(&[&(RS_PREFIX.as_bytes())], ctx.program_id);
+            Pubkey::find_program_address(&[RS_PREFIX.as_bytes(), &pool_id], ctx.program_id);
 
         // let _bump = ctx.bumps.get(RS_PREFIX).unwrap();
-        let pool_seeds = &[RS_PREFIX.as_bytes(), &[_bump]];
+        let pool_seeds = &[RS_PREFIX.as_bytes(), &pool_id, &[_bump]];
         let signer = &[&pool_seeds[..]];
 
         let token_accounts = anchor_spl::token::Transfer {
@@ -64,10 +81,18 @@ pub fn handle(ctx: Context<WithdrawSwrd>) -> Result<()> {
         };
         let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), token_accounts);
         msg!(
-            "Calling the token program to withdraw reward {} to the admin",
-            vault_amount
+            "Calling the token program to withdraw reward {} to the treasurer",
+            amount
         );
-        anchor_spl::token::transfer(cpi_ctx.with_signer(signer), vault_amount)?;
+        anchor_spl::token::transfer(cpi_ctx.with_signer(signer), amount)?;
     }
+
+    emit!(RewardWithdrawn {
+        pool: ctx.accounts.pool_account.key(),
+        treasurer: ctx.accounts.treasurer.key(),
+        amount,
+        vault_balance: vault_amount - amount,
+        timestamp: ctx.accounts.pool_account.last_update_time,
+    });
     Ok(())
 }
@@ -2,11 +2,13 @@ use anchor_lang::prelude::*;
 
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod states;
+pub mod utils;
 
-use constants::*;
 use instructions::*;
+use states::{AdminAction, ClassParams, CustodyMode, RewardMode, Role};
 
 declare_id!("7RdikeoWp1fzYyw6k1tpoULgZEQ33tFnRE3Nf111NBuu");
 
@@ -14,16 +16,35 @@ declare_id!("7RdikeoWp1fzYyw6k1tpoULgZEQ33tFnRE3Nf111NBuu");
 pub mod puffu_staking_program {
     use super::*;
 
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize_staking_pool(
         ctx: Context<InitializeStakingPool>,
-        reward_policy_by_class: [u16; CLASS_TYPES],
-        lock_day_by_class: [u16; CLASS_TYPES],
+        pool_id: u64,
+        collection: Pubkey,
+        class_root: [u8; 32],
+        custody_mode: CustodyMode,
+        reward_mode: RewardMode,
+        emission_per_second: u64,
+        config_delay: i64,
     ) -> Result<()> {
-        initialize::initialize_staking_pool(ctx, reward_policy_by_class, lock_day_by_class)
+        initialize::initialize_staking_pool(
+            ctx,
+            pool_id,
+            collection,
+            class_root,
+            custody_mode,
+            reward_mode,
+            emission_per_second,
+            config_delay,
+        )
     }
 
-    pub fn stake_nft(ctx: Context<StakeNft>, class_id: u32) -> Result<()> {
-        stake::stake_nft(ctx, class_id)
+    pub fn stake_nft(ctx: Context<StakeNft>, class_id: u32, proof: Vec<[u8; 32]>) -> Result<()> {
+        stake::stake_nft(ctx, class_id, proof)
+    }
+
+    pub fn request_unstake(ctx: Context<RequestUnstake>) -> Result<()> {
+        request_unstake::request_unstake(ctx)
     }
 
     pub fn withdraw_nft(ctx: Context<WithdrawNft>) -> Result<()> {
@@ -34,29 +55,130 @@ pub mod puffu_staking_program {
         claim_reward::claim_reward(ctx)
     }
 
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+        emergency_withdraw::emergency_withdraw(ctx)
+    }
+
+    pub fn stake_many<'info>(
+        ctx: Context<'_, '_, '_, 'info, StakeMany<'info>>,
+        class_ids: Vec<u32>,
+        proofs: Vec<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        stake_many::stake_many(ctx, class_ids, proofs)
+    }
+
+    pub fn withdraw_many<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawMany<'info>>,
+    ) -> Result<()> {
+        withdraw_many::withdraw_many(ctx)
+    }
+
+    pub fn claim_many<'info>(ctx: Context<'_, '_, '_, 'info, ClaimMany<'info>>) -> Result<()> {
+        claim_many::claim_many(ctx)
+    }
+
+    pub fn claim_unpaid(ctx: Context<ClaimUnpaid>) -> Result<()> {
+        claim_unpaid::claim_unpaid(ctx)
+    }
+
     pub fn deposit_swrd(ctx: Context<DepositSwrd>, amount: u64) -> Result<()> {
         // Transfer reward tokens into the vault.
         deposit_reward::handle(ctx, amount)
     }
 
-    pub fn withdraw_swrd(ctx: Context<WithdrawSwrd>) -> Result<()> {
-        withdraw_reward::handle(ctx)
+    pub fn queue_config_change(
+        ctx: Context<QueueConfigChange>,
+        runway_day: u16,
+        emission_per_second: u64,
+        max_staked_nft: u32,
+        max_staked_per_wallet: u32,
+    ) -> Result<()> {
+        queue_config_change::handle(
+            ctx,
+            runway_day,
+            emission_per_second,
+            max_staked_nft,
+            max_staked_per_wallet,
+        )
     }
 
-    pub fn change_pool_setting(
-        ctx: Context<ChangePoolSetting>,
-        reward_policy_by_class: [u16; CLASS_TYPES],
-        lock_day_by_class: [u16; CLASS_TYPES],
-        paused: bool,
-    ) -> Result<()> {
-        update_config::handle(ctx, reward_policy_by_class, lock_day_by_class, paused)
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        execute_config_change::handle(ctx)
+    }
+
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        cancel_config_change::handle(ctx)
+    }
+
+    pub fn add_class(ctx: Context<AddClass>, params: ClassParams) -> Result<()> {
+        add_class::handle(ctx, params)
+    }
+
+    pub fn queue_class_update(ctx: Context<QueueClassUpdate>, params: ClassParams) -> Result<()> {
+        queue_class_update::handle(ctx, params)
     }
 
-    pub fn change_reward_mint(ctx: Context<ChangeRewardMint>, reward_mint: Pubkey) -> Result<()> {
-        update_token_mint::handle(ctx, reward_mint)
+    pub fn execute_class_update(ctx: Context<ExecuteClassUpdate>) -> Result<()> {
+        execute_class_update::handle(ctx)
     }
 
-    pub fn transfer_ownership(ctx: Context<TransferOwnership>, new_admin: Pubkey) -> Result<()> {
-        transfer_ownership::handle(ctx, new_admin)
+    pub fn cancel_class_update(ctx: Context<CancelClassUpdate>) -> Result<()> {
+        cancel_class_update::handle(ctx)
+    }
+
+    pub fn set_class_enabled(ctx: Context<SetClassEnabled>, enabled: bool) -> Result<()> {
+        set_class_enabled::handle(ctx, enabled)
+    }
+
+    pub fn change_config_delay(ctx: Context<ChangeConfigDelay>, config_delay: i64) -> Result<()> {
+        update_config_delay::handle(ctx, config_delay)
+    }
+
+    pub fn set_penalty_treasury(ctx: Context<ChangePenaltyTreasury>) -> Result<()> {
+        update_penalty_treasury::handle(ctx)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, pause_flags: u8) -> Result<()> {
+        set_paused::handle(ctx, pause_flags)
+    }
+
+    pub fn change_class_root(ctx: Context<ChangeClassRoot>, class_root: [u8; 32]) -> Result<()> {
+        update_class_root::handle(ctx, class_root)
+    }
+
+    pub fn execute_class_root_change(ctx: Context<ExecuteClassRootChange>) -> Result<()> {
+        execute_class_root_change::handle(ctx)
+    }
+
+    pub fn cancel_class_root_change(ctx: Context<CancelClassRootChange>) -> Result<()> {
+        cancel_class_root_change::handle(ctx)
+    }
+
+    pub fn accept_ownership(ctx: Context<AcceptOwnership>) -> Result<()> {
+        accept_ownership::handle(ctx)
+    }
+
+    pub fn grant_role(ctx: Context<GrantRole>, role: Role, account: Pubkey) -> Result<()> {
+        grant_role::handle(ctx, role, account)
+    }
+
+    pub fn revoke_role(ctx: Context<RevokeRole>, role: Role) -> Result<()> {
+        revoke_role::handle(ctx, role)
+    }
+
+    // `withdraw_swrd`, `change_reward_mint`, `transfer_ownership` and `cancel_ownership_transfer`
+    // only run through proposals
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: AdminAction) -> Result<()> {
+        create_proposal::handle(ctx, action)
+    }
+
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        approve_proposal::handle(ctx)
+    }
+
+    pub fn execute_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>,
+    ) -> Result<()> {
+        execute_proposal::handle(ctx)
     }
 }
@@ -0,0 +1,193 @@
+use crate::{constants::*, error::*, states::StakeInfo};
+use anchor_lang::prelude::*;
+use std::convert::TryFrom;
+
+/// Settings of a class set by `add_class` and `queue_class_update`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct ClassParams {
+    /// Display name, utf-8 padded with zeros
+    pub name: [u8; CLASS_NAME_LEN],
+    /// Reward amount per day per nft, over `reward_denominator`
+    pub reward_per_day: u64,
+    pub reward_denominator: u64,
+    pub lock_day: u16,
+    /// Unbonding days between `request_unstake` and withdrawal, 0 allows withdrawing directly
+    pub cooldown_day: u16,
+    /// Share of the reward forfeited for unstaking before the lock ends, 0 keeps the class locked
+    pub early_unstake_penalty_bps: u16,
+    /// Most nfts staked in the class at once, 0 for no limit
+    pub max_stakers: u32,
+}
+
+impl ClassParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.reward_denominator > 0,
+            StakingError::InvalidRewardDenominator
+        );
+        require!(
+            self.early_unstake_penalty_bps as u64 <= BPS_DENOMINATOR,
+            StakingError::InvalidPenalty
+        );
+        // unbonding classes can only be left after the lock, so a penalty would never apply
+        require!(
+            self.cooldown_day == 0 || self.early_unstake_penalty_bps == 0,
+            StakingError::PenaltyWithCooldown
+        );
+        Ok(())
+    }
+}
+
+/// Class update waiting out the pool `config_delay` before it can be executed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct QueuedClassUpdate {
+    pub params: ClassParams,
+    /// Earliest time `execute_class_update` applies it
+    pub eta: i64,
+}
+
+#[account]
+#[derive(Default)]
+pub struct ClassConfig {
+    pub pool: Pubkey,
+    pub class_id: u32,
+    /// Disabled classes take no new stakes, staked nfts keep earning
+    pub enabled: bool,
+    /// Stakes are taken from this time, `config_delay` after the class was added
+    pub active_from: i64,
+    pub name: [u8; CLASS_NAME_LEN],
+    /// Reward amount per day per nft, over `reward_denominator`
+    pub reward_per_day: u64,
+    pub reward_denominator: u64,
+    pub lock_day: u16,
+    /// Unbonding days between `request_unstake` and withdrawal, 0 allows withdrawing directly
+    pub cooldown_day: u16,
+    /// Share of the reward forfeited for unstaking before the lock ends, 0 keeps the class locked
+    pub early_unstake_penalty_bps: u16,
+    /// Most nfts staked in the class at once, 0 for no limit
+    pub max_stakers: u32,
+    /// Tokens staked in the class
+    pub staked_nft: u32,
+    /// Update waiting to be executed
+    pub queued_update: Option<QueuedClassUpdate>,
+    /// Reward one nft earned in the class so far, in whole tokens scaled by `ACC_PRECISION`
+    pub acc_reward_per_nft: u128,
+    /// Pool accrual clock time `acc_reward_per_nft` is accrued up to
+    pub last_accrual_time: i64,
+}
+
+impl ClassConfig {
+    pub fn params(&self) -> ClassParams {
+        ClassParams {
+            name: self.name,
+            reward_per_day: self.reward_per_day,
+            reward_denominator: self.reward_denominator,
+            lock_day: self.lock_day,
+            cooldown_day: self.cooldown_day,
+            early_unstake_penalty_bps: self.early_unstake_penalty_bps,
+            max_stakers: self.max_stakers,
+        }
+    }
+
+    /// `acc_reward_per_nft` at `accrual_time` on the pool accrual clock, at the current rate
+    pub fn reward_per_nft_at(&self, accrual_time: i64) -> Result<u128> {
+        // a class added without a rate has no denominator yet
+        if accrual_time <= self.last_accrual_time || self.reward_per_day == 0 {
+            return Ok(self.acc_reward_per_nft);
+        }
+        Ok((self.reward_per_day as u128)
+            .checked_mul(ACC_PRECISION)
+            .and_then(|amount| amount.checked_mul((accrual_time - self.last_accrual_time) as u128))
+            .and_then(|amount| {
+                amount.checked_div((self.reward_denominator as u128).checked_mul(DAY as u128)?)
+            })
+            .and_then(|amount| amount.checked_add(self.acc_reward_per_nft))
+            .ok_or(StakingError::MathOverflow)?)
+    }
+
+    /// Accrue `acc_reward_per_nft` up to `accrual_time`, must run before the rate changes.
+    pub fn accrue(&mut self, accrual_time: i64) -> Result<()> {
+        self.acc_reward_per_nft = self.reward_per_nft_at(accrual_time)?;
+        self.last_accrual_time = self.last_accrual_time.max(accrual_time);
+        Ok(())
+    }
+
+    /// Reward of one nft per day in base units of a token with `unit_amount`
+    pub fn daily_reward(&self, unit_amount: u64) -> Result<u64> {
+        if self.reward_per_day == 0 {
+            return Ok(0);
+        }
+        Ok((self.reward_per_day as u128)
+            .checked_mul(unit_amount as u128)
+            .and_then(|amount| amount.checked_div(self.reward_denominator as u128))
+            .and_then(|amount| u64::try_from(amount).ok())
+            .ok_or(StakingError::MathOverflow)?)
+    }
+
+    /// End of the lock period of `staking_info`
+    pub fn unlock_time(&self, staking_info: &StakeInfo) -> Result<i64> {
+        Ok(staking_info
+            .stake_time
+            .checked_add((self.lock_day as i64) * DAY)
+            .ok_or(StakingError::MathOverflow)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_a_penalty_with_a_cooldown() {
+        let params = ClassParams {
+            reward_denominator: 1,
+            cooldown_day: 3,
+            early_unstake_penalty_bps: 500,
+            ..ClassParams::default()
+        };
+        assert!(params.validate().is_err());
+        assert!(ClassParams {
+            cooldown_day: 0,
+            ..params
+        }
+        .validate()
+        .is_ok());
+        assert!(ClassParams {
+            early_unstake_penalty_bps: BPS_DENOMINATOR as u16 + 1,
+            cooldown_day: 0,
+            ..params
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn reward_per_nft_accrues_at_the_current_rate() {
+        let mut class = ClassConfig {
+            reward_per_day: 3,
+            reward_denominator: 2,
+            last_accrual_time: DAY,
+            ..ClassConfig::default()
+        };
+        assert_eq!(class.reward_per_nft_at(0).unwrap(), 0);
+        assert_eq!(class.reward_per_nft_at(3 * DAY).unwrap(), 3 * ACC_PRECISION);
+
+        class.accrue(3 * DAY).unwrap();
+        class.reward_per_day = 1;
+        class.reward_denominator = 1;
+        assert_eq!(class.last_accrual_time, 3 * DAY);
+        assert_eq!(class.reward_per_nft_at(4 * DAY).unwrap(), 4 * ACC_PRECISION);
+    }
+
+    #[test]
+    fn reward_per_nft_overflow_is_an_error() {
+        let class = ClassConfig {
+            reward_per_day: u64::MAX,
+            reward_denominator: 1,
+            ..ClassConfig::default()
+        };
+        assert!(class.reward_per_nft_at(DAY).is_ok());
+        assert!(class.reward_per_nft_at(i64::MAX).is_err());
+        assert!(class.daily_reward(1_000_000).is_err());
+    }
+}
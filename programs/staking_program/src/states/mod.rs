@@ -1,5 +1,11 @@
 pub mod pool_state;
 pub use pool_state::*;
 
+pub mod class_config;
+pub use class_config::*;
+
 pub mod user_state;
 pub use user_state::*;
+
+pub mod proposal;
+pub use proposal::*;
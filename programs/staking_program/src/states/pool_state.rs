@@ -1,15 +1,86 @@
-use crate::constants::*;
+use crate::{
+    constants::*,
+    error::*,
+    states::{ClassConfig, ClassParams, StakeInfo, StakeStatus, UserState},
+};
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use std::convert::TryFrom;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum CustodyMode {
+    /// Stakes are bookkeeping only, the NFT stays in the owner's wallet
+    Bookkeeping,
+    /// The NFT is moved into the pool's `RS_STAKE_SEED` token account
+    Escrow,
+    /// The NFT stays in the owner's wallet, delegated to and frozen by the pool
+    Freeze,
+}
+
+// `#[default]` on enum variants needs a newer rustc than the bpf toolchain
+#[allow(clippy::derivable_impls)]
+impl Default for CustodyMode {
+    fn default() -> Self {
+        CustodyMode::Bookkeeping
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RewardMode {
+    /// Each NFT earns the `reward_per_day / reward_denominator` tokens of its class per day
+    PerNft,
+    /// The pool emits `emission_per_second` split by class weight
+    SharedEmission,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for RewardMode {
+    fn default() -> Self {
+        RewardMode::PerNft
+    }
+}
+
+/// Config change waiting out `config_delay` before it can be executed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct QueuedConfig {
+    pub runway_day: u16,
+    pub emission_per_second: u64,
+    pub max_staked_nft: u32,
+    pub max_staked_per_wallet: u32,
+    /// Earliest time `execute_config_change` applies it
+    pub eta: i64,
+}
+
+/// Class root waiting out `config_delay` before it can be executed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct QueuedClassRoot {
+    pub class_root: [u8; 32],
+    /// Earliest time `execute_class_root_change` applies it
+    pub eta: i64,
+}
+
+/// Operational roles the admin hands out, one key per role
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// Pauses and unpauses the pool
+    Pauser,
+    /// Sets the penalty treasury, treasury withdrawals name their recipient in the proposal
+    Treasurer,
+    /// Manages classes, runway, emission and staking limits
+    ConfigManager,
+}
 
 #[account]
 #[derive(Default)]
 pub struct PoolConfig {
     // 1
     pub is_initialized: bool,
+    /// Admin-chosen id the pool PDA is derived from
+    pub pool_id: u64,
     /// admin pubkey
     pub admin: Pubkey,
-    /// Paused state of the program
-    pub paused: bool,
+    /// `PAUSE_*` flags of the operations currently stopped
+    pub pause_flags: u8,
     /// nft lock period
     pub lock_day: u32,
     /// Mint of the reward token.
@@ -20,8 +91,1200 @@ pub struct PoolConfig {
     pub last_update_time: i64,
     /// Tokens Staked
     pub staked_nft: u32,
-    /// Reward amount per day according to class type
-    pub reward_policy_by_class: [u16; CLASS_TYPES],
-    /// lock day according to type
-    pub lock_day_by_class: [u16; CLASS_TYPES],
+    /// Verified Metaplex collection that stakeable NFTs must belong to
+    pub collection: Pubkey,
+    /// Merkle root of (nft mint, class id) leaves published by the admin
+    pub class_root: [u8; 32],
+    /// How staked NFTs are held, fixed at initialization
+    pub custody_mode: CustodyMode,
+    /// Rewards owed to stakers that the vault could not cover yet
+    pub total_unpaid_reward: u64,
+    /// Rewards accrued by stakers and not paid out yet, including unpaid debt
+    pub accrued_reward: u64,
+    /// Days of emission the vault keeps on top of accrued rewards
+    pub runway_day: u16,
+    /// How rewards are computed, fixed at initialization
+    pub reward_mode: RewardMode,
+    /// Base units emitted per second in `SharedEmission` mode
+    pub emission_per_second: u64,
+    /// Sum of the weights of staked NFTs
+    pub total_weight: u64,
+    /// Reward accumulated per unit of weight, scaled by `ACC_PRECISION`
+    pub acc_reward_per_weight: u128,
+    /// Admin proposed by `transfer_ownership`, takes over once it accepts
+    pub pending_admin: Pubkey,
+    /// Key holding each `Role`, indexed by the role
+    pub role_holders: [Pubkey; ROLE_TYPES],
+    /// Keys that approve proposals, unused slots are `Pubkey::default()`
+    pub multisig_signers: [Pubkey; MAX_MULTISIG_SIGNERS],
+    /// Approvals a proposal needs before it can be executed
+    pub multisig_threshold: u8,
+    /// Bumped on every signer set change so older approvals no longer count
+    pub multisig_version: u32,
+    /// Id of the next proposal
+    pub proposal_count: u64,
+    /// Seconds between queueing a config change and executing it
+    pub config_delay: i64,
+    /// Config change waiting to be executed
+    pub queued_config: Option<QueuedConfig>,
+    /// Token account receiving early-unstake penalties, the reward vault keeps them by default
+    pub penalty_treasury: Pubkey,
+    /// Decimals of `reward_mint`, whole-token rates are scaled by them
+    pub reward_decimals: u8,
+    /// Classes added so far, the next `ClassConfig` gets this id
+    pub class_count: u32,
+    /// Reward emitted per day by all staked NFTs in `PerNft` mode, in reward token base units
+    pub nft_emission_per_day: u64,
+    /// When `PAUSE_ACCRUAL` was last set
+    pub accrual_paused_at: i64,
+    /// Seconds accrual has been paused in total, not counting a pause still running
+    pub accrual_paused_time: i64,
+    /// Most nfts staked in the pool at once, 0 for no limit
+    pub max_staked_nft: u32,
+    /// Most nfts a wallet can have staked in the pool, 0 for no limit
+    pub max_staked_per_wallet: u32,
+    /// Class root waiting to be executed
+    pub queued_class_root: Option<QueuedClassRoot>,
+}
+
+impl PoolConfig {
+    pub fn has_role(&self, role: Role, key: &Pubkey) -> bool {
+        self.role_holders[role as usize] == *key
+    }
+
+    /// Slot of `key` in `multisig_signers`
+    pub fn multisig_index(&self, key: &Pubkey) -> Option<usize> {
+        if *key == Pubkey::default() {
+            return None;
+        }
+        self.multisig_signers
+            .iter()
+            .position(|signer| signer == key)
+    }
+
+    /// Check that the non-empty `signers` are distinct and can reach `threshold`.
+    pub fn validate_multisig(
+        signers: &[Pubkey; MAX_MULTISIG_SIGNERS],
+        threshold: u8,
+    ) -> Result<()> {
+        let mut count = 0;
+        for (i, signer) in signers.iter().enumerate() {
+            if *signer == Pubkey::default() {
+                continue;
+            }
+            require!(
+                !signers[..i].contains(signer),
+                StakingError::InvalidMultisig
+            );
+            count += 1;
+        }
+        require!(
+            threshold > 0 && threshold as usize <= count,
+            StakingError::InvalidMultisig
+        );
+        Ok(())
+    }
+
+    /// Check that `(nft_mint, class_id)` is a leaf of `class_root`.
+    /// Leaves are `keccak(mint || class_id as le u32)` and pairs are hashed in sorted order.
+    pub fn verify_class(&self, nft_mint: &Pubkey, class_id: u32, proof: &[[u8; 32]]) -> bool {
+        let mut node = keccak::hashv(&[nft_mint.as_ref(), &class_id.to_le_bytes()]).0;
+        for sibling in proof.iter() {
+            node = if node <= *sibling {
+                keccak::hashv(&[&node, sibling]).0
+            } else {
+                keccak::hashv(&[sibling, &node]).0
+            };
+        }
+        node == self.class_root
+    }
+
+    /// Pay the unpaid debt of `staking_info` plus the newly `accrued` reward out of `vault_balance`.
+    /// Whatever the vault cannot cover is carried as debt on the stake and on the pool.
+    pub fn settle_reward(
+        &mut self,
+        staking_info: &mut StakeInfo,
+        accrued: u64,
+        vault_balance: u64,
+    ) -> Result<u64> {
+        let owed = staking_info
+            .unpaid_reward
+            .checked_add(accrued)
+            .ok_or(StakingError::MathOverflow)?;
+        let reward = owed.min(vault_balance);
+        let shortfall = owed - reward;
+
+        self.total_unpaid_reward = self
+            .total_unpaid_reward
+            .checked_sub(staking_info.unpaid_reward)
+            .and_then(|total| total.checked_add(shortfall))
+            .ok_or(StakingError::MathOverflow)?;
+        staking_info.unpaid_reward = shortfall;
+        self.accrued_reward = self.accrued_reward.saturating_sub(reward);
+        Ok(reward)
+    }
+
+    /// Pay as much of the wallet debt as `vault_balance` covers, returning the amount to pay.
+    pub fn pay_user_debt(&mut self, user_state: &mut UserState, vault_balance: u64) -> Result<u64> {
+        let reward = user_state.unpaid_reward.min(vault_balance);
+        self.total_unpaid_reward = self
+            .total_unpaid_reward
+            .checked_sub(reward)
+            .ok_or(StakingError::MathOverflow)?;
+        self.accrued_reward = self.accrued_reward.saturating_sub(reward);
+        user_state.unpaid_reward -= reward;
+        Ok(reward)
+    }
+
+    /// Base units of one whole reward token
+    pub fn unit_amount(&self) -> Result<u64> {
+        Ok(10u64
+            .checked_pow(self.reward_decimals as u32)
+            .ok_or(StakingError::MathOverflow)?)
+    }
+
+    /// Reward emitted per day by all staked NFTs, in reward token base units
+    pub fn emission_per_day(&self) -> Result<u64> {
+        match self.reward_mode {
+            RewardMode::PerNft => Ok(self.nft_emission_per_day),
+            RewardMode::SharedEmission => {
+                if self.total_weight == 0 {
+                    return Ok(0);
+                }
+                Ok(self
+                    .emission_per_second
+                    .checked_mul(DAY as u64)
+                    .ok_or(StakingError::MathOverflow)?)
+            }
+        }
+    }
+
+    /// Accrue the pool-wide reward liabilities and the reward accumulator up to `now`.
+    /// Must run before staked weights, class rates or `emission_per_second` change.
+    /// Nothing accrues while `PAUSE_ACCRUAL` is set.
+    pub fn update_pool(&mut self, now: i64) -> Result<()> {
+        if now > self.last_update_time && !self.is_paused(PAUSE_ACCRUAL) {
+            let accrued = (self.emission_per_day()? as u128)
+                .checked_mul((now - self.last_update_time) as u128)
+                .and_then(|amount| amount.checked_div(DAY as u128))
+                .and_then(|amount| u64::try_from(amount).ok())
+                .ok_or(StakingError::MathOverflow)?;
+            self.accrued_reward = self
+                .accrued_reward
+                .checked_add(accrued)
+                .ok_or(StakingError::MathOverflow)?;
+
+            if self.reward_mode == RewardMode::SharedEmission && self.total_weight > 0 {
+                self.acc_reward_per_weight = (accrued as u128)
+                    .checked_mul(ACC_PRECISION)
+                    .and_then(|amount| amount.checked_div(self.total_weight as u128))
+                    .and_then(|amount| amount.checked_add(self.acc_reward_per_weight))
+                    .ok_or(StakingError::MathOverflow)?;
+            }
+        }
+        self.last_update_time = now;
+        Ok(())
+    }
+
+    /// Time on the accrual clock at `now`, which stands still while `PAUSE_ACCRUAL` is set.
+    /// Class reward accumulators and per-nft accrual are measured on it.
+    pub fn accrual_time(&self, now: i64) -> i64 {
+        let end = if self.is_paused(PAUSE_ACCRUAL) {
+            self.accrual_paused_at
+        } else {
+            now
+        };
+        end - self.accrual_paused_time
+    }
+
+    /// Reward earned by `staking_info` since its last update, `update_pool` must run first.
+    pub fn accrue_reward(
+        &self,
+        staking_info: &mut StakeInfo,
+        class: &ClassConfig,
+        now: i64,
+    ) -> Result<u64> {
+        match self.reward_mode {
+            RewardMode::PerNft => staking_info.update_reward(
+                now,
+                class.reward_per_nft_at(self.accrual_time(now))?,
+                self.unit_amount()?,
+            ),
+            RewardMode::SharedEmission => {
+                staking_info.update_shared_reward(now, self.acc_reward_per_weight)
+            }
+        }
+    }
+
+    pub fn is_paused(&self, flag: u8) -> bool {
+        self.pause_flags & flag != 0
+    }
+
+    /// Replace the pause flags at `now`, stopping or resuming reward accrual with `PAUSE_ACCRUAL`.
+    pub fn set_pause_flags(&mut self, pause_flags: u8, now: i64) -> Result<()> {
+        self.update_pool(now)?;
+        let accrual_paused = self.is_paused(PAUSE_ACCRUAL);
+        self.pause_flags = pause_flags;
+        match (accrual_paused, self.is_paused(PAUSE_ACCRUAL)) {
+            (false, true) => self.accrual_paused_at = now,
+            // move the accrual clock past the paused window
+            (true, false) => {
+                self.accrual_paused_time = now
+                    .checked_sub(self.accrual_paused_at)
+                    .and_then(|paused| self.accrual_paused_time.checked_add(paused))
+                    .ok_or(StakingError::MathOverflow)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Apply `params` to `class` at `now`, staked nfts settle the time before it at the old rate.
+    pub fn set_class_params(
+        &mut self,
+        class: &mut ClassConfig,
+        params: &ClassParams,
+        now: i64,
+    ) -> Result<()> {
+        self.update_pool(now)?;
+        let unit_amount = self.unit_amount()?;
+        let old_emission = (class.staked_nft as u64)
+            .checked_mul(class.daily_reward(unit_amount)?)
+            .ok_or(StakingError::MathOverflow)?;
+
+        // stakes settle the time before this at the old rate,
+        // while paused the new rate takes effect when the pool resumes
+        class.accrue(self.accrual_time(now))?;
+        class.name = params.name;
+        class.reward_per_day = params.reward_per_day;
+        class.reward_denominator = params.reward_denominator;
+        class.lock_day = params.lock_day;
+        class.cooldown_day = params.cooldown_day;
+        class.early_unstake_penalty_bps = params.early_unstake_penalty_bps;
+        class.max_stakers = params.max_stakers;
+
+        let new_emission = (class.staked_nft as u64)
+            .checked_mul(class.daily_reward(unit_amount)?)
+            .ok_or(StakingError::MathOverflow)?;
+        // rounding after a reward mint change can leave the total slightly below the classes
+        self.nft_emission_per_day = self
+            .nft_emission_per_day
+            .saturating_sub(old_emission)
+            .checked_add(new_emission)
+            .ok_or(StakingError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Start earning for a new stake at `now` with the weight of its class.
+    pub fn add_stake(
+        &mut self,
+        staking_info: &mut StakeInfo,
+        class: &mut ClassConfig,
+        user_state: &mut UserState,
+        now: i64,
+    ) -> Result<()> {
+        require!(class.enabled, StakingError::ClassDisabled);
+        require!(now >= class.active_from, StakingError::ClassNotActive);
+        require!(
+            self.max_staked_nft == 0 || self.staked_nft < self.max_staked_nft,
+            StakingError::PoolFull
+        );
+        require!(
+            class.max_stakers == 0 || class.staked_nft < class.max_stakers,
+            StakingError::ClassFull
+        );
+        require!(
+            self.max_staked_per_wallet == 0 || user_state.staked_count < self.max_staked_per_wallet,
+            StakingError::WalletLimitReached
+        );
+
+        let daily_reward = class.daily_reward(self.unit_amount()?)?;
+        staking_info.weight = daily_reward;
+        staking_info.reward_debt = (staking_info.weight as u128)
+            .checked_mul(self.acc_reward_per_weight)
+            .ok_or(StakingError::MathOverflow)?
+            / ACC_PRECISION;
+        staking_info.reward_per_nft_paid = class.reward_per_nft_at(self.accrual_time(now))?;
+
+        user_state.staked_count = user_state
+            .staked_count
+            .checked_add(1)
+            .ok_or(StakingError::MathOverflow)?;
+        self.staked_nft = self
+            .staked_nft
+            .checked_add(1)
+            .ok_or(StakingError::MathOverflow)?;
+        class.staked_nft = class
+            .staked_nft
+            .checked_add(1)
+            .ok_or(StakingError::MathOverflow)?;
+        self.total_weight = self
+            .total_weight
+            .checked_add(staking_info.weight)
+            .ok_or(StakingError::MathOverflow)?;
+        self.nft_emission_per_day = self
+            .nft_emission_per_day
+            .checked_add(daily_reward)
+            .ok_or(StakingError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Stop earning for a stake that leaves the pool.
+    /// It stops counting against the pool, class and wallet caps at the same time.
+    pub fn remove_stake(
+        &mut self,
+        staking_info: &StakeInfo,
+        class: &mut ClassConfig,
+        user_state: &mut UserState,
+    ) -> Result<()> {
+        user_state.staked_count = user_state
+            .staked_count
+            .checked_sub(1)
+            .ok_or(StakingError::MathOverflow)?;
+        self.staked_nft = self
+            .staked_nft
+            .checked_sub(1)
+            .ok_or(StakingError::MathOverflow)?;
+        class.staked_nft = class
+            .staked_nft
+            .checked_sub(1)
+            .ok_or(StakingError::MathOverflow)?;
+        self.total_weight = self
+            .total_weight
+            .checked_sub(staking_info.weight)
+            .ok_or(StakingError::MathOverflow)?;
+        // the class rate may have changed since the stake, its current rate is in the total
+        self.nft_emission_per_day = self
+            .nft_emission_per_day
+            .saturating_sub(class.daily_reward(self.unit_amount()?)?);
+        Ok(())
+    }
+
+    /// Settle the reward of `staking_info` up to `now` and return the amount to pay from the vault.
+    /// Classes with an early-unstake penalty only pay out once the lock is over.
+    pub fn claim(
+        &mut self,
+        staking_info: &mut StakeInfo,
+        class: &ClassConfig,
+        now: i64,
+        vault_balance: u64,
+    ) -> Result<u64> {
+        // otherwise claiming right before an early unstake would leave nothing to cut
+        require!(
+            class.early_unstake_penalty_bps == 0 || class.unlock_time(staking_info)? < now,
+            StakingError::RewardLocked
+        );
+        self.update_pool(now)?;
+        let accrued = self.accrue_reward(staking_info, class, now)?;
+        self.settle_reward(staking_info, accrued, vault_balance)
+    }
+
+    /// Stop a stake from earning once its lock is over and start the class cooldown.
+    /// Its reward so far is carried as unpaid debt until it is claimed or withdrawn.
+    pub fn request_unstake(
+        &mut self,
+        staking_info: &mut StakeInfo,
+        class: &mut ClassConfig,
+        user_state: &mut UserState,
+        now: i64,
+    ) -> Result<()> {
+        require!(
+            staking_info.status == StakeStatus::Staked,
+            StakingError::AlreadyUnbonding
+        );
+        require!(
+            class.unlock_time(staking_info)? < now,
+            StakingError::InvalidWithdrawTime
+        );
+
+        self.update_pool(now)?;
+        let accrued = self.accrue_reward(staking_info, class, now)?;
+        self.settle_reward(staking_info, accrued, 0)?;
+        self.remove_stake(staking_info, class, user_state)?;
+
+        staking_info.status = StakeStatus::Unbonding;
+        staking_info.unbond_time = now;
+        staking_info.cooldown_end = now
+            .checked_add((class.cooldown_day as i64) * DAY)
+            .ok_or(StakingError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Settle and remove a stake, returning the reward to pay and the early-unstake penalty.
+    /// Before the lock period is over the class penalty is cut from the reward,
+    /// classes without a penalty stay locked.
+    /// The stake info is closed afterwards, so what the vault cannot pay moves to the wallet debt.
+    pub fn unstake(
+        &mut self,
+        staking_info: &mut StakeInfo,
+        class: &mut ClassConfig,
+        user_state: &mut UserState,
+        now: i64,
+        vault_balance: u64,
+    ) -> Result<(u64, u64)> {
+        let penalty_bps = match staking_info.status {
+            StakeStatus::Unbonding => {
+                require!(
+                    staking_info.cooldown_end <= now,
+                    StakingError::CooldownNotOver
+                );
+                0
+            }
+            StakeStatus::Staked => {
+                require!(class.cooldown_day == 0, StakingError::UnbondingRequired);
+                if class.unlock_time(staking_info)? < now {
+                    0
+                } else {
+                    let penalty_bps = class.early_unstake_penalty_bps;
+                    require!(penalty_bps > 0, StakingError::InvalidWithdrawTime);
+                    penalty_bps
+                }
+            }
+        };
+
+        self.update_pool(now)?;
+        let accrued = self.accrue_reward(staking_info, class, now)?;
+        let owed = staking_info
+            .unpaid_reward
+            .checked_add(accrued)
+            .ok_or(StakingError::MathOverflow)?;
+        // only the reward earned by the stake is cut, not debt the vault failed to pay,
+        // at most `accrued`, so it fits back into a u64
+        let penalty = ((accrued as u128) * (penalty_bps as u128) / BPS_DENOMINATOR as u128) as u64;
+        let reward = (owed - penalty).min(vault_balance);
+        let shortfall = owed - penalty - reward;
+
+        self.total_unpaid_reward = self
+            .total_unpaid_reward
+            .checked_sub(staking_info.unpaid_reward)
+            .and_then(|total| total.checked_add(shortfall))
+            .ok_or(StakingError::MathOverflow)?;
+        // the penalty is no longer owed to anyone, whether or not it leaves the vault
+        self.accrued_reward = self.accrued_reward.saturating_sub(reward + penalty);
+        staking_info.unpaid_reward = 0;
+        user_state.unpaid_reward = user_state
+            .unpaid_reward
+            .checked_add(shortfall)
+            .ok_or(StakingError::MathOverflow)?;
+
+        // unbonding stakes left the pool with the request
+        if staking_info.status == StakeStatus::Staked {
+            self.remove_stake(staking_info, class, user_state)?;
+        }
+        Ok((reward, penalty))
+    }
+
+    /// Whether penalties are moved out of the reward vault to a separate treasury
+    pub fn has_penalty_treasury(&self) -> bool {
+        self.penalty_treasury != self.reward_vault
+    }
+
+    /// Remove a stake without paying it and drop its reward from the pool liabilities.
+    /// Returns the forfeited reward.
+    pub fn forfeit(
+        &mut self,
+        staking_info: &mut StakeInfo,
+        class: &mut ClassConfig,
+        user_state: &mut UserState,
+        now: i64,
+    ) -> Result<u64> {
+        self.update_pool(now)?;
+        let accrued = self.accrue_reward(staking_info, class, now)?;
+        let forfeited = staking_info
+            .unpaid_reward
+            .checked_add(accrued)
+            .ok_or(StakingError::MathOverflow)?;
+
+        self.total_unpaid_reward = self
+            .total_unpaid_reward
+            .checked_sub(staking_info.unpaid_reward)
+            .ok_or(StakingError::MathOverflow)?;
+        self.accrued_reward = self.accrued_reward.saturating_sub(forfeited);
+        staking_info.unpaid_reward = 0;
+
+        if staking_info.status == StakeStatus::Staked {
+            self.remove_stake(staking_info, class, user_state)?;
+        }
+        Ok(forfeited)
+    }
+
+    /// Switch the reward liabilities to a mint with `reward_decimals`, `update_pool` must run first.
+    /// Per-nft rewards are recomputed in the new units at claim time, only the pool totals are rescaled.
+    pub fn set_reward_decimals(&mut self, reward_decimals: u8) -> Result<()> {
+        if reward_decimals == self.reward_decimals {
+            return Ok(());
+        }
+        require!(
+            self.reward_mode == RewardMode::PerNft && self.total_unpaid_reward == 0,
+            StakingError::RewardDecimalsMismatch
+        );
+
+        self.accrued_reward = self.rescale(self.accrued_reward, reward_decimals)?;
+        self.nft_emission_per_day = self.rescale(self.nft_emission_per_day, reward_decimals)?;
+        self.reward_decimals = reward_decimals;
+        Ok(())
+    }
+
+    /// `amount` in base units of a mint with `reward_decimals`
+    fn rescale(&self, amount: u64, reward_decimals: u8) -> Result<u64> {
+        if reward_decimals > self.reward_decimals {
+            Ok(10u64
+                .checked_pow((reward_decimals - self.reward_decimals) as u32)
+                .and_then(|scale| amount.checked_mul(scale))
+                .ok_or(StakingError::MathOverflow)?)
+        } else {
+            Ok(10u64
+                .checked_pow((self.reward_decimals - reward_decimals) as u32)
+                .map(|scale| amount / scale)
+                .ok_or(StakingError::MathOverflow)?)
+        }
+    }
+
+    /// Reward the vault must keep: accrued liabilities plus `runway_day` days of emission
+    pub fn reserved_reward(&self) -> Result<u64> {
+        Ok(self
+            .emission_per_day()?
+            .checked_mul(self.runway_day as u64)
+            .and_then(|reserved| reserved.checked_add(self.accrued_reward))
+            .ok_or(StakingError::MathOverflow)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: i64 = 1_000_000;
+    const UNIT: u64 = 1_000_000;
+
+    fn pool() -> PoolConfig {
+        PoolConfig {
+            reward_decimals: 6,
+            last_update_time: START,
+            ..PoolConfig::default()
+        }
+    }
+
+    fn params(reward_per_day: u64) -> ClassParams {
+        ClassParams {
+            reward_per_day,
+            reward_denominator: 1,
+            ..ClassParams::default()
+        }
+    }
+
+    fn class(pool: &mut PoolConfig, params: ClassParams) -> ClassConfig {
+        let mut class = ClassConfig {
+            enabled: true,
+            ..ClassConfig::default()
+        };
+        pool.set_class_params(&mut class, &params, START).unwrap();
+        class
+    }
+
+    fn stake(
+        pool: &mut PoolConfig,
+        class: &mut ClassConfig,
+        user_state: &mut UserState,
+        now: i64,
+    ) -> StakeInfo {
+        let mut staking_info = StakeInfo {
+            stake_time: now,
+            last_update_time: now,
+            ..StakeInfo::default()
+        };
+        pool.update_pool(now).unwrap();
+        pool.add_stake(&mut staking_info, class, user_state, now)
+            .unwrap();
+        staking_info
+    }
+
+    fn assert_error<T: std::fmt::Debug>(result: Result<T>, error: StakingError) {
+        match result.unwrap_err() {
+            Error::AnchorError(err) => assert_eq!(err.error_code_number, u32::from(error)),
+            err => panic!("unexpected error {:?}", err),
+        }
+    }
+
+    fn penalty_class(pool: &mut PoolConfig) -> ClassConfig {
+        class(
+            pool,
+            ClassParams {
+                lock_day: 7,
+                early_unstake_penalty_bps: 1_000,
+                ..params(10)
+            },
+        )
+    }
+
+    #[test]
+    fn unstake_cuts_the_penalty_before_the_lock_ends() {
+        let mut pool = pool();
+        let mut class = penalty_class(&mut pool);
+        let mut user_state = UserState::default();
+        let mut staking_info = stake(&mut pool, &mut class, &mut user_state, START);
+
+        let (reward, penalty) = pool
+            .unstake(
+                &mut staking_info,
+                &mut class,
+                &mut user_state,
+                START + DAY,
+                u64::MAX,
+            )
+            .unwrap();
+        assert_eq!((reward, penalty), (9 * UNIT, UNIT));
+        assert_eq!(pool.staked_nft, 0);
+        assert_eq!(class.staked_nft, 0);
+        assert_eq!(pool.accrued_reward, 0);
+    }
+
+    #[test]
+    fn claim_then_withdraw_early_still_pays_the_penalty() {
+        let mut pool = pool();
+        let mut class = penalty_class(&mut pool);
+        let mut user_state = UserState::default();
+        let mut staking_info = stake(&mut pool, &mut class, &mut user_state, START);
+
+        assert_error(
+            pool.claim(&mut staking_info, &class, START + DAY, u64::MAX),
+            StakingError::RewardLocked,
+        );
+        let (reward, penalty) = pool
+            .unstake(
+                &mut staking_info,
+                &mut class,
+                &mut user_state,
+                START + DAY,
+                u64::MAX,
+            )
+            .unwrap();
+        assert_eq!((reward, penalty), (9 * UNIT, UNIT));
+    }
+
+    #[test]
+    fn penalty_is_not_cut_from_unpaid_debt() {
+        let mut pool = pool();
+        let mut class = penalty_class(&mut pool);
+        let mut user_state = UserState::default();
+        let mut staking_info = stake(&mut pool, &mut class, &mut user_state, START);
+        staking_info.unpaid_reward = 5 * UNIT;
+        pool.total_unpaid_reward = 5 * UNIT;
+        pool.accrued_reward = 5 * UNIT;
+
+        let (reward, penalty) = pool
+            .unstake(
+                &mut staking_info,
+                &mut class,
+                &mut user_state,
+                START + DAY,
+                u64::MAX,
+            )
+            .unwrap();
+        assert_eq!((reward, penalty), (14 * UNIT, UNIT));
+        assert_eq!(pool.total_unpaid_reward, 0);
+        assert_eq!(pool.accrued_reward, 0);
+    }
+
+    #[test]
+    fn claim_is_open_after_the_lock() {
+        let mut pool = pool();
+        let mut class = penalty_class(&mut pool);
+        let mut staking_info = stake(&mut pool, &mut class, &mut UserState::default(), START);
+
+        let now = START + 8 * DAY;
+        assert_eq!(
+            pool.claim(&mut staking_info, &class, now, u64::MAX)
+                .unwrap(),
+            80 * UNIT
+        );
+    }
+
+    #[test]
+    fn caps_limit_the_pool_class_and_wallet() {
+        let mut pool = pool();
+        let mut capped = class(
+            &mut pool,
+            ClassParams {
+                max_stakers: 2,
+                ..params(10)
+            },
+        );
+        pool.max_staked_nft = 3;
+        pool.max_staked_per_wallet = 1;
+
+        let (mut first, mut second) = (UserState::default(), UserState::default());
+        stake(&mut pool, &mut capped, &mut first, START);
+        assert_error(
+            pool.add_stake(&mut StakeInfo::default(), &mut capped, &mut first, START),
+            StakingError::WalletLimitReached,
+        );
+        stake(&mut pool, &mut capped, &mut second, START);
+        assert_error(
+            pool.add_stake(
+                &mut StakeInfo::default(),
+                &mut capped,
+                &mut UserState::default(),
+                START,
+            ),
+            StakingError::ClassFull,
+        );
+
+        let mut other_class = class(&mut pool, params(10));
+        stake(
+            &mut pool,
+            &mut other_class,
+            &mut UserState::default(),
+            START,
+        );
+        assert_error(
+            pool.add_stake(
+                &mut StakeInfo::default(),
+                &mut other_class,
+                &mut UserState::default(),
+                START,
+            ),
+            StakingError::PoolFull,
+        );
+    }
+
+    #[test]
+    fn unbonding_stakes_leave_every_cap() {
+        let mut pool = pool();
+        let mut class = class(
+            &mut pool,
+            ClassParams {
+                cooldown_day: 3,
+                max_stakers: 1,
+                ..params(10)
+            },
+        );
+        pool.max_staked_nft = 1;
+        pool.max_staked_per_wallet = 1;
+        let mut user_state = UserState::default();
+        let mut staking_info = stake(&mut pool, &mut class, &mut user_state, START);
+
+        pool.request_unstake(&mut staking_info, &mut class, &mut user_state, START + DAY)
+            .unwrap();
+        assert_eq!(
+            (pool.staked_nft, class.staked_nft, user_state.staked_count),
+            (0, 0, 0)
+        );
+        stake(&mut pool, &mut class, &mut user_state, START + DAY);
+        assert_eq!(
+            (pool.staked_nft, class.staked_nft, user_state.staked_count),
+            (1, 1, 1)
+        );
+    }
+
+    fn leaf(nft_mint: &Pubkey, class_id: u32) -> [u8; 32] {
+        keccak::hashv(&[nft_mint.as_ref(), &class_id.to_le_bytes()]).0
+    }
+
+    #[test]
+    fn verify_class_checks_the_proof_against_the_root() {
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (first_leaf, second_leaf) = (leaf(&first, 1), leaf(&second, 2));
+        let mut pool = pool();
+        pool.class_root = if first_leaf <= second_leaf {
+            keccak::hashv(&[&first_leaf, &second_leaf]).0
+        } else {
+            keccak::hashv(&[&second_leaf, &first_leaf]).0
+        };
+
+        assert!(pool.verify_class(&first, 1, &[second_leaf]));
+        assert!(pool.verify_class(&second, 2, &[first_leaf]));
+        assert!(!pool.verify_class(&first, 2, &[second_leaf]));
+        assert!(!pool.verify_class(&first, 1, &[]));
+    }
+
+    #[test]
+    fn settle_reward_carries_the_shortfall() {
+        let mut pool = pool();
+        let mut staking_info = StakeInfo::default();
+        pool.accrued_reward = 100;
+
+        assert_eq!(pool.settle_reward(&mut staking_info, 100, 60).unwrap(), 60);
+        assert_eq!(staking_info.unpaid_reward, 40);
+        assert_eq!(pool.total_unpaid_reward, 40);
+        assert_eq!(pool.accrued_reward, 40);
+
+        assert_eq!(pool.settle_reward(&mut staking_info, 0, 100).unwrap(), 40);
+        assert_eq!(staking_info.unpaid_reward, 0);
+        assert_eq!(pool.total_unpaid_reward, 0);
+        assert_eq!(pool.accrued_reward, 0);
+    }
+
+    #[test]
+    fn unstake_moves_the_shortfall_to_the_wallet() {
+        let mut pool = pool();
+        let mut class = class(&mut pool, params(10));
+        let mut user_state = UserState::default();
+        let mut staking_info = stake(&mut pool, &mut class, &mut user_state, START);
+
+        let (reward, _) = pool
+            .unstake(
+                &mut staking_info,
+                &mut class,
+                &mut user_state,
+                START + DAY,
+                4 * UNIT,
+            )
+            .unwrap();
+        assert_eq!(reward, 4 * UNIT);
+        assert_eq!(user_state.unpaid_reward, 6 * UNIT);
+        assert_eq!(pool.total_unpaid_reward, 6 * UNIT);
+
+        assert_eq!(
+            pool.pay_user_debt(&mut user_state, u64::MAX).unwrap(),
+            6 * UNIT
+        );
+        assert_eq!(user_state.unpaid_reward, 0);
+        assert_eq!(pool.total_unpaid_reward, 0);
+        assert_eq!(pool.accrued_reward, 0);
+    }
+
+    #[test]
+    fn shared_emission_splits_by_class_weight() {
+        let mut pool = PoolConfig {
+            reward_mode: RewardMode::SharedEmission,
+            emission_per_second: 40,
+            ..pool()
+        };
+        let mut small = class(&mut pool, params(1));
+        let mut big = class(&mut pool, params(3));
+        let mut user_state = UserState::default();
+
+        // nothing is emitted while the pool is empty
+        pool.update_pool(START + DAY).unwrap();
+        assert_eq!(pool.accrued_reward, 0);
+
+        let mut first = stake(&mut pool, &mut small, &mut user_state, START + DAY);
+        let mut second = stake(&mut pool, &mut big, &mut user_state, START + DAY);
+        let now = START + 2 * DAY;
+        assert_eq!(
+            pool.claim(&mut first, &small, now, u64::MAX).unwrap(),
+            864_000
+        );
+        assert_eq!(
+            pool.claim(&mut second, &big, now, u64::MAX).unwrap(),
+            2_592_000
+        );
+
+        let mut third = stake(&mut pool, &mut small, &mut user_state, now);
+        let now = START + 3 * DAY;
+        assert_eq!(
+            pool.claim(&mut first, &small, now, u64::MAX).unwrap(),
+            691_200
+        );
+        assert_eq!(
+            pool.claim(&mut second, &big, now, u64::MAX).unwrap(),
+            2_073_600
+        );
+        assert_eq!(
+            pool.claim(&mut third, &small, now, u64::MAX).unwrap(),
+            691_200
+        );
+        assert_eq!(pool.accrued_reward, 0);
+    }
+
+    #[test]
+    fn validate_multisig_needs_distinct_signers_reaching_the_threshold() {
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut signers = [Pubkey::default(); MAX_MULTISIG_SIGNERS];
+        signers[0] = first;
+        signers[2] = second;
+
+        assert!(PoolConfig::validate_multisig(&signers, 2).is_ok());
+        assert_error(
+            PoolConfig::validate_multisig(&signers, 0),
+            StakingError::InvalidMultisig,
+        );
+        assert_error(
+            PoolConfig::validate_multisig(&signers, 3),
+            StakingError::InvalidMultisig,
+        );
+        signers[1] = first;
+        assert_error(
+            PoolConfig::validate_multisig(&signers, 1),
+            StakingError::InvalidMultisig,
+        );
+    }
+
+    #[test]
+    fn empty_multisig_slots_are_not_signers() {
+        let signer = Pubkey::new_unique();
+        let mut pool = pool();
+        pool.multisig_signers[1] = signer;
+
+        assert_eq!(pool.multisig_index(&signer), Some(1));
+        assert_eq!(pool.multisig_index(&Pubkey::default()), None);
+        assert_eq!(pool.multisig_index(&Pubkey::new_unique()), None);
+    }
+
+    #[test]
+    fn per_nft_reward_uses_each_rate_for_its_period() {
+        let mut pool = pool();
+        let mut class = class(&mut pool, params(10));
+        let mut user_state = UserState::default();
+        let mut first = stake(&mut pool, &mut class, &mut user_state, START);
+
+        pool.set_class_params(&mut class, &params(20), START + DAY)
+            .unwrap();
+        let mut second = stake(&mut pool, &mut class, &mut user_state, START + DAY);
+
+        let now = START + 2 * DAY;
+        assert_eq!(
+            pool.claim(&mut first, &class, now, u64::MAX).unwrap(),
+            30 * UNIT
+        );
+        assert_eq!(
+            pool.claim(&mut second, &class, now, u64::MAX).unwrap(),
+            20 * UNIT
+        );
+        assert_eq!(pool.accrued_reward, 0);
+    }
+
+    #[test]
+    fn set_pause_flags_stops_the_accrual_clock() {
+        let mut pool = pool();
+        let mut class = class(&mut pool, params(10));
+        let mut user_state = UserState::default();
+        let mut staking_info = stake(&mut pool, &mut class, &mut user_state, START);
+
+        pool.set_pause_flags(PAUSE_ACCRUAL, START + DAY).unwrap();
+        assert_eq!(pool.accrual_time(START + 2 * DAY), START + DAY);
+        pool.set_pause_flags(PAUSE_CLAIM, START + 3 * DAY).unwrap();
+        assert!(!pool.is_paused(PAUSE_ACCRUAL));
+        assert_eq!(pool.accrual_paused_time, 2 * DAY);
+        assert_eq!(pool.accrual_time(START + 4 * DAY), START + 2 * DAY);
+
+        let now = START + 4 * DAY;
+        assert_eq!(
+            pool.claim(&mut staking_info, &class, now, u64::MAX)
+                .unwrap(),
+            20 * UNIT
+        );
+        assert_eq!(pool.accrued_reward, 0);
+    }
+
+    #[test]
+    fn forfeit_drops_the_reward_from_the_liabilities() {
+        let mut pool = pool();
+        let mut class = class(&mut pool, params(10));
+        let mut user_state = UserState::default();
+        let mut staking_info = stake(&mut pool, &mut class, &mut user_state, START);
+
+        pool.set_pause_flags(PAUSE_ACCRUAL, START + DAY).unwrap();
+        assert_eq!(
+            pool.forfeit(
+                &mut staking_info,
+                &mut class,
+                &mut user_state,
+                START + 2 * DAY
+            )
+            .unwrap(),
+            10 * UNIT
+        );
+        assert_eq!(pool.accrued_reward, 0);
+        assert_eq!(pool.nft_emission_per_day, 0);
+        assert_eq!(user_state.staked_count, 0);
+    }
+
+    #[test]
+    fn cooldown_classes_unbond_before_the_withdrawal() {
+        let mut pool = pool();
+        let mut class = class(
+            &mut pool,
+            ClassParams {
+                cooldown_day: 3,
+                ..params(10)
+            },
+        );
+        let mut user_state = UserState::default();
+        let mut staking_info = stake(&mut pool, &mut class, &mut user_state, START);
+
+        assert_error(
+            pool.unstake(
+                &mut staking_info,
+                &mut class,
+                &mut user_state,
+                START + DAY,
+                u64::MAX,
+            ),
+            StakingError::UnbondingRequired,
+        );
+        pool.request_unstake(&mut staking_info, &mut class, &mut user_state, START + DAY)
+            .unwrap();
+        assert_eq!(staking_info.cooldown_end, START + 4 * DAY);
+        assert_eq!(staking_info.unpaid_reward, 10 * UNIT);
+        assert_error(
+            pool.request_unstake(&mut staking_info, &mut class, &mut user_state, START + DAY),
+            StakingError::AlreadyUnbonding,
+        );
+        assert_error(
+            pool.unstake(
+                &mut staking_info,
+                &mut class,
+                &mut user_state,
+                START + 2 * DAY,
+                u64::MAX,
+            ),
+            StakingError::CooldownNotOver,
+        );
+
+        // nothing accrues during the cooldown
+        let (reward, penalty) = pool
+            .unstake(
+                &mut staking_info,
+                &mut class,
+                &mut user_state,
+                START + 4 * DAY,
+                u64::MAX,
+            )
+            .unwrap();
+        assert_eq!((reward, penalty), (10 * UNIT, 0));
+        assert_eq!(pool.total_unpaid_reward, 0);
+        assert_eq!(pool.accrued_reward, 0);
+    }
+
+    #[test]
+    fn request_unstake_waits_for_the_lock() {
+        let mut pool = pool();
+        let mut class = class(
+            &mut pool,
+            ClassParams {
+                lock_day: 7,
+                cooldown_day: 3,
+                ..params(10)
+            },
+        );
+        let mut user_state = UserState::default();
+        let mut staking_info = stake(&mut pool, &mut class, &mut user_state, START);
+
+        assert_error(
+            pool.request_unstake(&mut staking_info, &mut class, &mut user_state, START + DAY),
+            StakingError::InvalidWithdrawTime,
+        );
+    }
+
+    #[test]
+    fn set_reward_decimals_rescales_the_liabilities() {
+        let mut pool = pool();
+        let mut class = class(&mut pool, params(10));
+        let mut user_state = UserState::default();
+        let mut staking_info = stake(&mut pool, &mut class, &mut user_state, START);
+
+        pool.update_pool(START + DAY).unwrap();
+        pool.set_reward_decimals(9).unwrap();
+        assert_eq!(pool.accrued_reward, 10_000_000_000);
+        assert_eq!(pool.nft_emission_per_day, 10_000_000_000);
+        assert_eq!(
+            pool.claim(&mut staking_info, &class, START + 2 * DAY, u64::MAX)
+                .unwrap(),
+            20_000_000_000
+        );
+        assert_eq!(pool.accrued_reward, 0);
+
+        pool.set_reward_decimals(3).unwrap();
+        assert_eq!(pool.nft_emission_per_day, 10_000);
+        assert_error(pool.set_reward_decimals(30), StakingError::MathOverflow);
+    }
+
+    #[test]
+    fn set_reward_decimals_refuses_unpaid_debt_and_shared_emission() {
+        let mut indebted = PoolConfig {
+            total_unpaid_reward: 1,
+            ..pool()
+        };
+        assert_error(
+            indebted.set_reward_decimals(9),
+            StakingError::RewardDecimalsMismatch,
+        );
+        assert!(indebted.set_reward_decimals(6).is_ok());
+
+        let mut shared = PoolConfig {
+            reward_mode: RewardMode::SharedEmission,
+            ..pool()
+        };
+        assert_error(
+            shared.set_reward_decimals(9),
+            StakingError::RewardDecimalsMismatch,
+        );
+    }
+
+    #[test]
+    fn fractional_rates_do_not_lose_reward_to_rounding() {
+        let mut pool = pool();
+        let mut class = class(
+            &mut pool,
+            ClassParams {
+                reward_per_day: 1,
+                reward_denominator: 3,
+                ..ClassParams::default()
+            },
+        );
+        let mut user_state = UserState::default();
+        let mut staking_info = stake(&mut pool, &mut class, &mut user_state, START);
+
+        assert_eq!(pool.nft_emission_per_day, 333_333);
+        assert_eq!(
+            pool.claim(&mut staking_info, &class, START + 3 * DAY, u64::MAX)
+                .unwrap(),
+            UNIT
+        );
+    }
+
+    #[test]
+    fn oversized_rates_fail_with_math_overflow() {
+        let mut pool = pool();
+        let mut class = class(&mut pool, params(10));
+        assert_error(
+            pool.set_class_params(&mut class, &params(u64::MAX), START),
+            StakingError::MathOverflow,
+        );
+
+        pool.reward_decimals = 20;
+        assert_error(pool.unit_amount(), StakingError::MathOverflow);
+    }
+
+    #[test]
+    fn added_classes_open_after_the_config_delay() {
+        let mut pool = PoolConfig {
+            config_delay: 2 * DAY,
+            ..pool()
+        };
+        // a new class account starts zeroed, without a denominator
+        let mut class = ClassConfig {
+            enabled: true,
+            active_from: START + pool.config_delay,
+            ..ClassConfig::default()
+        };
+        pool.set_class_params(&mut class, &params(10), START)
+            .unwrap();
+        let mut user_state = UserState::default();
+
+        assert_error(
+            pool.add_stake(
+                &mut StakeInfo::default(),
+                &mut class,
+                &mut user_state,
+                START + DAY,
+            ),
+            StakingError::ClassNotActive,
+        );
+        stake(&mut pool, &mut class, &mut user_state, START + 2 * DAY);
+        assert_eq!(pool.nft_emission_per_day, 10 * UNIT);
+
+        class.enabled = false;
+        assert_error(
+            pool.add_stake(
+                &mut StakeInfo::default(),
+                &mut class,
+                &mut user_state,
+                START + 2 * DAY,
+            ),
+            StakingError::ClassDisabled,
+        );
+    }
 }
@@ -0,0 +1,50 @@
+use crate::constants::*;
+use anchor_lang::prelude::*;
+
+/// Admin action a proposal carries out once the multisig threshold approves it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AdminAction {
+    /// `withdraw_swrd` of `amount` to `treasurer`
+    WithdrawReward { amount: u64, treasurer: Pubkey },
+    /// `change_reward_mint` to `new_mint`, the old vault balance goes to `treasurer`
+    ChangeRewardMint { new_mint: Pubkey, treasurer: Pubkey },
+    /// `transfer_ownership` to `new_admin`
+    TransferOwnership { new_admin: Pubkey },
+    /// `cancel_ownership_transfer` of the pending admin
+    CancelOwnershipTransfer,
+    /// Replace the signer set, unused slots are `Pubkey::default()`
+    ChangeMultisig {
+        signers: [Pubkey; MAX_MULTISIG_SIGNERS],
+        threshold: u8,
+    },
+}
+
+impl Default for AdminAction {
+    fn default() -> Self {
+        AdminAction::WithdrawReward {
+            amount: 0,
+            treasurer: Pubkey::default(),
+        }
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct Proposal {
+    pub pool: Pubkey,
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub action: AdminAction,
+    /// Approval per slot of the pool `multisig_signers`
+    pub approvals: [bool; MAX_MULTISIG_SIGNERS],
+    /// Pool `multisig_version` the approvals were given under
+    pub multisig_version: u32,
+    pub executed: bool,
+    pub created_at: i64,
+}
+
+impl Proposal {
+    pub fn approval_count(&self) -> u8 {
+        self.approvals.iter().filter(|approved| **approved).count() as u8
+    }
+}
@@ -1,5 +1,21 @@
-use crate::constants::*;
+use crate::{constants::*, error::*};
 use anchor_lang::prelude::*;
+use std::convert::TryFrom;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum StakeStatus {
+    /// Earning rewards
+    Staked,
+    /// Unstake requested, no longer earning and waiting out the class cooldown
+    Unbonding,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for StakeStatus {
+    fn default() -> Self {
+        StakeStatus::Staked
+    }
+}
 
 #[account]
 #[derive(Default)]
@@ -9,27 +25,91 @@ pub struct StakeInfo {
     pub nft_addr: Pubkey,
     pub stake_time: i64,
     pub last_update_time: i64,
+    /// Reward accrued but not paid because the vault was short
+    pub unpaid_reward: u64,
+    /// Class weight at stake time, used in `SharedEmission` mode
+    pub weight: u64,
+    /// `weight * acc_reward_per_weight` already accounted for this stake
+    pub reward_debt: u128,
+    pub status: StakeStatus,
+    /// When the unstake was requested, accrual stops here
+    pub unbond_time: i64,
+    /// When an unbonding stake can be withdrawn
+    pub cooldown_end: i64,
+    /// Class `acc_reward_per_nft` the `PerNft` reward is settled up to
+    pub reward_per_nft_paid: u128,
+}
+
+#[account]
+#[derive(Default)]
+pub struct UserState {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    /// NFTs the wallet currently has staked in the pool, unbonding ones no longer count
+    pub staked_count: u32,
+    /// Reward paid to the wallet over its lifetime
+    pub total_claimed: u64,
+    pub last_claim_time: i64,
+    /// Reward of withdrawn nfts the vault could not pay, paid before the next claim
+    pub unpaid_reward: u64,
+}
+
+impl UserState {
+    pub fn record_claim(&mut self, reward: u64, now: i64) -> Result<()> {
+        self.total_claimed = self
+            .total_claimed
+            .checked_add(reward)
+            .ok_or(StakingError::MathOverflow)?;
+        self.last_claim_time = now;
+        Ok(())
+    }
 }
 
 impl StakeInfo {
-    pub fn update_reward(&mut self, now: i64, reward_per_day: u16) -> Result<u64> {
-        let mut last_reward_time = self.last_update_time;
-        if last_reward_time < self.stake_time {
-            last_reward_time = self.stake_time;
+    /// Reward since the last update, `acc_reward_per_nft` is the current value of the class.
+    pub fn update_reward(
+        &mut self,
+        now: i64,
+        acc_reward_per_nft: u128,
+        unit_amount: u64,
+    ) -> Result<u64> {
+        // nothing accrues after the unstake request, it was settled then
+        if self.status == StakeStatus::Unbonding {
+            self.last_update_time = now;
+            return Ok(0);
         }
 
-        let unit_amount = (10 as u64).pow(DECIMAL);
-        let reward = (unit_amount as u128)
-            .checked_mul((now as u128).checked_sub(last_reward_time as u128).unwrap())
-            .unwrap()
-            .checked_mul(reward_per_day as u128)
-            .unwrap()
-            .checked_div(DAY as u128)
-            .unwrap() as u64;
+        let reward = acc_reward_per_nft
+            .checked_sub(self.reward_per_nft_paid)
+            .and_then(|amount| amount.checked_mul(unit_amount as u128))
+            .map(|amount| amount / ACC_PRECISION)
+            .and_then(|reward| u64::try_from(reward).ok())
+            .ok_or(StakingError::MathOverflow)?;
         // reward = (((now - last_reward_time) / DAY) as u64) * reward_per_day;
+        self.reward_per_nft_paid = acc_reward_per_nft;
         self.last_update_time = now;
 
         Ok(reward)
         // return Ok(reward);
     }
+
+    pub fn update_shared_reward(&mut self, now: i64, acc_reward_per_weight: u128) -> Result<u64> {
+        // the weight left the pool with the unstake request
+        if self.status == StakeStatus::Unbonding {
+            self.last_update_time = now;
+            return Ok(0);
+        }
+        let accumulated = (self.weight as u128)
+            .checked_mul(acc_reward_per_weight)
+            .ok_or(StakingError::MathOverflow)?
+            / ACC_PRECISION;
+        let reward = accumulated
+            .checked_sub(self.reward_debt)
+            .and_then(|reward| u64::try_from(reward).ok())
+            .ok_or(StakingError::MathOverflow)?;
+        self.reward_debt = accumulated;
+        self.last_update_time = now;
+
+        Ok(reward)
+    }
 }
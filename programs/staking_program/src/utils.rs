@@ -0,0 +1,260 @@
+use crate::{error::*, states::*};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount};
+use anchor_spl::token::{self, Approve, CloseAccount, Revoke, Transfer};
+use mpl_token_metadata::{
+    instruction::{freeze_delegated_account, thaw_delegated_account},
+    state::{Metadata, TokenMetadataAccount},
+};
+
+/// Check that `nft_mint` is a verified member of the pool collection
+/// and that `class_id` is the class published for it in `class_root`.
+pub fn verify_nft(
+    pool_account: &PoolConfig,
+    nft_mint: &Pubkey,
+    nft_metadata: &AccountInfo,
+    class_id: u32,
+    proof: &[[u8; 32]],
+) -> Result<()> {
+    require!(
+        pool_account.verify_class(nft_mint, class_id, proof),
+        StakingError::InvalidClassProof
+    );
+
+    let metadata = Metadata::from_account_info(nft_metadata)?;
+    require!(metadata.mint == *nft_mint, StakingError::InvalidCollection);
+    match metadata.collection {
+        Some(collection) => require!(
+            collection.verified && collection.key == pool_account.collection,
+            StakingError::InvalidCollection
+        ),
+        None => return err!(StakingError::InvalidCollection),
+    }
+    Ok(())
+}
+
+/// Create the `account` PDA of `space` bytes owned by `owner`, signed with its `seeds`.
+/// Like Anchor's `init`, an address that already holds lamports is topped up, allocated
+/// and assigned instead, so nobody can block it by sending lamports there first.
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    rent: &Rent,
+    space: usize,
+    owner: &Pubkey,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let required_lamports = rent.minimum_balance(space);
+    let lamports = account.lamports();
+    if lamports == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                &[seeds],
+            ),
+            required_lamports,
+            space as u64,
+            owner,
+        );
+    }
+
+    if required_lamports > lamports {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            required_lamports - lamports,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate {
+                account_to_allocate: account.clone(),
+            },
+            &[seeds],
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Assign {
+                account_to_assign: account.clone(),
+            },
+            &[seeds],
+        ),
+        owner,
+    )
+}
+
+/// Class accounts of a batch instruction, each one is loaded once
+/// so that nfts of the same class update the same copy
+#[derive(Default)]
+pub struct ClassCache<'info> {
+    classes: Vec<Account<'info, ClassConfig>>,
+}
+
+impl<'info> ClassCache<'info> {
+    /// Load the `class_id` class of `pool` from `class_info` unless it is already cached.
+    pub fn load(
+        &mut self,
+        class_info: &AccountInfo<'info>,
+        pool: &Pubkey,
+        class_id: u32,
+    ) -> Result<&mut Account<'info, ClassConfig>> {
+        let index = match self
+            .classes
+            .iter()
+            .position(|class| class.key() == class_info.key())
+        {
+            Some(index) => index,
+            None => {
+                self.classes
+                    .push(Account::<ClassConfig>::try_from(class_info)?);
+                self.classes.len() - 1
+            }
+        };
+        let class = &mut self.classes[index];
+        require!(
+            class.pool == *pool && class.class_id == class_id,
+            StakingError::InvalidClassId
+        );
+        Ok(class)
+    }
+
+    /// Write the cached classes back to their accounts.
+    pub fn exit(&self, program_id: &Pubkey) -> Result<()> {
+        for class in self.classes.iter() {
+            class.exit(program_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Accounts holding one staked NFT, shared by the single and batch instructions
+pub struct NftCustody<'info> {
+    pub owner: AccountInfo<'info>,
+    pub pool_account: AccountInfo<'info>,
+    pub nft_mint: AccountInfo<'info>,
+    pub nft_edition: AccountInfo<'info>,
+    pub user_nft_token_account: AccountInfo<'info>,
+    pub staked_nft_token_account: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub token_metadata_program: AccountInfo<'info>,
+}
+
+impl<'info> NftCustody<'info> {
+    /// Take custody of the NFT according to the pool custody mode.
+    /// `signer` are the pool account seeds.
+    pub fn lock(&self, custody_mode: CustodyMode, signer: &[&[&[u8]]]) -> Result<()> {
+        match custody_mode {
+            CustodyMode::Bookkeeping => {}
+            // transfer nft to pda when the pool holds custody
+            CustodyMode::Escrow => {
+                let cpi_accounts = Transfer {
+                    from: self.user_nft_token_account.clone(),
+                    to: self.staked_nft_token_account.clone(),
+                    authority: self.owner.clone(),
+                };
+                let transfer_ctx = CpiContext::new(self.token_program.clone(), cpi_accounts);
+                token::transfer(transfer_ctx, 1)?;
+            }
+            // delegate the nft to the pool and freeze it in the owner's wallet
+            CustodyMode::Freeze => {
+                let cpi_accounts = Approve {
+                    to: self.user_nft_token_account.clone(),
+                    delegate: self.pool_account.clone(),
+                    authority: self.owner.clone(),
+                };
+                let approve_ctx = CpiContext::new(self.token_program.clone(), cpi_accounts);
+                token::approve(approve_ctx, 1)?;
+
+                invoke_signed(
+                    &freeze_delegated_account(
+                        mpl_token_metadata::id(),
+                        self.pool_account.key(),
+                        self.user_nft_token_account.key(),
+                        self.nft_edition.key(),
+                        self.nft_mint.key(),
+                    ),
+                    &[
+                        self.pool_account.clone(),
+                        self.user_nft_token_account.clone(),
+                        self.nft_edition.clone(),
+                        self.nft_mint.clone(),
+                        self.token_program.clone(),
+                        self.token_metadata_program.clone(),
+                    ],
+                    signer,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Hand the NFT back to the owner and close the escrow token account to refund its rent.
+    /// `signer` are the pool account seeds.
+    pub fn release(&self, custody_mode: CustodyMode, signer: &[&[&[u8]]]) -> Result<()> {
+        match custody_mode {
+            CustodyMode::Bookkeeping => {}
+            CustodyMode::Escrow => {
+                let cpi_accounts = Transfer {
+                    from: self.staked_nft_token_account.clone(),
+                    to: self.user_nft_token_account.clone(),
+                    authority: self.pool_account.clone(),
+                };
+                let transfer_ctx =
+                    CpiContext::new_with_signer(self.token_program.clone(), cpi_accounts, signer);
+                token::transfer(transfer_ctx, 1)?;
+            }
+            // thaw the nft in the owner's wallet and give the delegate back
+            CustodyMode::Freeze => {
+                invoke_signed(
+                    &thaw_delegated_account(
+                        mpl_token_metadata::id(),
+                        self.pool_account.key(),
+                        self.user_nft_token_account.key(),
+                        self.nft_edition.key(),
+                        self.nft_mint.key(),
+                    ),
+                    &[
+                        self.pool_account.clone(),
+                        self.user_nft_token_account.clone(),
+                        self.nft_edition.clone(),
+                        self.nft_mint.clone(),
+                        self.token_program.clone(),
+                        self.token_metadata_program.clone(),
+                    ],
+                    signer,
+                )?;
+
+                let cpi_accounts = Revoke {
+                    source: self.user_nft_token_account.clone(),
+                    authority: self.owner.clone(),
+                };
+                let revoke_ctx = CpiContext::new(self.token_program.clone(), cpi_accounts);
+                token::revoke(revoke_ctx)?;
+            }
+        }
+
+        let cpi_accounts = CloseAccount {
+            account: self.staked_nft_token_account.clone(),
+            destination: self.owner.clone(),
+            authority: self.pool_account.clone(),
+        };
+        let close_ctx =
+            CpiContext::new_with_signer(self.token_program.clone(), cpi_accounts, signer);
+        token::close_account(close_ctx)
+    }
+}
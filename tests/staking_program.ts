@@ -1,58 +1,176 @@
 import * as anchor from '@project-serum/anchor';
 import { Program } from '@project-serum/anchor';
-import { RsStakingProgram } from '../target/types/rs_staking_program';
-import { SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { PuffuStakingProgram } from '../target/types/puffu_staking_program';
+import { SystemProgram, SYSVAR_RENT_PUBKEY, Transaction } from "@solana/web3.js";
 import { Token, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  PROGRAM_ID as TOKEN_METADATA_PROGRAM_ID,
+  createCreateMetadataAccountV2Instruction,
+  createCreateMasterEditionV3Instruction,
+  createVerifyCollectionInstruction,
+} from "@metaplex-foundation/mpl-token-metadata";
+import { keccak_256 } from "js-sha3";
 import { assert } from "chai";
-import bs58 from 'bs58';
-import { ASSOCIATED_PROGRAM_ID } from '@project-serum/anchor/dist/cjs/utils/token';
 
 const PublicKey = anchor.web3.PublicKey;
 
 const SWRD_DECIMAL = 6;
-const GLOBAL_AUTHORITY_SEED = "global-authority";
-const RS_PREFIX = "rs-nft-staking";
-const RS_STAKEINFO_SEED = "rs-stake-info";
-const RS_STAKE_SEED = "rs-nft-staking";
-const RS_VAULT_SEED = "rs-vault";
-
-
+const RS_PREFIX = "puffu-nft-staking";
+const RS_VAULT_SEED = "puffu-vault";
+const RS_PROPOSAL_SEED = "puffu-proposal";
+const RS_CLASS_SEED = "puffu-class";
+const RS_STAKEINFO_SEED = "puffu-stake-info";
+const RS_STAKE_SEED = "puffu-nft-staking";
+const RS_USER_STATE_SEED = "puffu-user-state";
+
+const DAY = 60 * 60 * 24;
+const DEFAULT_CONFIG_DELAY = 2 * DAY;
+const PAUSE_DEPOSIT = 1 << 3;
+const CLASS_NAME_LEN = 32;
+
+// the admin flows run on a pool with the default config delay, staking runs on a second pool
+// without one so its class takes stakes right away, the reward math is covered by the unit tests
 describe('staking_program', () => {
 
   // Configure the client to use the local cluster.
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
 
-  const program = anchor.workspace.RsStakingProgram as Program<RsStakingProgram>;
+  const program = anchor.workspace.PuffuStakingProgram as Program<PuffuStakingProgram>;
   const superOwner = anchor.web3.Keypair.generate();
   const user = anchor.web3.Keypair.generate();
 
-  // const USER_POOL_SIZE = 2064;
-  // const GLOBAL_POOL_SIZE = 360_016;
+  const pool_id = new anchor.BN(1);
+  const staking_pool_id = new anchor.BN(2);
 
-  let nft_token_mint = null as Token;
   let reward_mint = null as Token;
+  let funder_vault_account = null;
+  let pool_account_pda = null;
+  let vault_pda = null;
+  let class_pda = null;
+
+  let collection_mint = null as Token;
+  let nft_token_mint = null as Token;
   let user_nft_token_account = null;
+  let outsider_nft = null;
   let user_reward_account = null;
-  let funder_vault_account = null;
+  let staking_pool_pda = null;
+  let staking_vault_pda = null;
+  let staking_class_pda = null;
 
   let initial_reward_vault_amount = 1_000_000_000;
   let deposit_amount = 100_000_000;
-  let class_types = [
-    10,
-    25,
-    30,
-    40,
-    55,
-    60,
-    70,
-    80,
-    90,
-  ];
-  let lock_day = 0;
+
+  const u32Bytes = (value: number) => {
+    const bytes = Buffer.alloc(4);
+    bytes.writeUInt32LE(value);
+    return bytes;
+  };
+
+  const classParams = (name: string, reward_per_day: number, lock_day: number, cooldown_day: number, penalty_bps: number) => {
+    const name_bytes = Buffer.alloc(CLASS_NAME_LEN);
+    name_bytes.write(name);
+    return {
+      name: Array.from(name_bytes),
+      rewardPerDay: new anchor.BN(reward_per_day),
+      rewardDenominator: new anchor.BN(1),
+      lockDay: lock_day,
+      cooldownDay: cooldown_day,
+      earlyUnstakePenaltyBps: penalty_bps,
+      maxStakers: 0,
+    };
+  };
+
+  const metadataPda = async (mint: anchor.web3.PublicKey) => (await PublicKey.findProgramAddress(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  ))[0];
+
+  const editionPda = async (mint: anchor.web3.PublicKey) => (await PublicKey.findProgramAddress(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from("edition")],
+    TOKEN_METADATA_PROGRAM_ID
+  ))[0];
+
+  // mint a Metaplex master edition to `owner`, verified as a member of `collection` when given
+  const createNft = async (owner: anchor.web3.PublicKey, collection: Token | null) => {
+    const mint = await Token.createMint(
+      provider.connection,
+      superOwner,
+      superOwner.publicKey,
+      null,
+      0,
+      TOKEN_PROGRAM_ID
+    );
+    const token_account = await mint.createAccount(owner);
+    await mint.mintTo(token_account, superOwner, [], 1);
+
+    const metadata = await metadataPda(mint.publicKey);
+    const tx = new Transaction().add(
+      createCreateMetadataAccountV2Instruction({
+        metadata,
+        mint: mint.publicKey,
+        mintAuthority: superOwner.publicKey,
+        payer: superOwner.publicKey,
+        updateAuthority: superOwner.publicKey,
+      }, {
+        createMetadataAccountArgsV2: {
+          data: {
+            name: "Puffu",
+            symbol: "PUFFU",
+            uri: "",
+            sellerFeeBasisPoints: 0,
+            creators: null,
+            collection: collection ? { key: collection.publicKey, verified: false } : null,
+            uses: null,
+          },
+          isMutable: true,
+        },
+      }),
+      createCreateMasterEditionV3Instruction({
+        edition: await editionPda(mint.publicKey),
+        mint: mint.publicKey,
+        updateAuthority: superOwner.publicKey,
+        mintAuthority: superOwner.publicKey,
+        payer: superOwner.publicKey,
+        metadata,
+      }, {
+        createMasterEditionArgs: { maxSupply: 0 },
+      }),
+    );
+    if (collection) {
+      tx.add(createVerifyCollectionInstruction({
+        metadata,
+        collectionAuthority: superOwner.publicKey,
+        payer: superOwner.publicKey,
+        collectionMint: collection.publicKey,
+        collection: await metadataPda(collection.publicKey),
+        collectionMasterEditionAccount: await editionPda(collection.publicKey),
+      }));
+    }
+    await provider.sendAndConfirm(tx, [superOwner]);
+    return { mint, token_account };
+  };
+
+  const classLeaf = (mint: anchor.web3.PublicKey, class_id: number) =>
+    Buffer.from(keccak_256.arrayBuffer(Buffer.concat([mint.toBuffer(), u32Bytes(class_id)])));
+
+  // pairs are hashed in sorted order, each leaf is the proof of the other
+  const classRoot = (first: Buffer, second: Buffer) =>
+    Array.from(Buffer.from(keccak_256.arrayBuffer(
+      Buffer.compare(first, second) <= 0 ? Buffer.concat([first, second]) : Buffer.concat([second, first])
+    )));
+
+  const expectError = async (promise: Promise<any>, code: string) => {
+    try {
+      await promise;
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, code);
+      return;
+    }
+    assert.fail("expected " + code);
+  };
 
   it('Is initialized!', async () => {
-    // Add your test here.
     await provider.connection.confirmTransaction(
       await provider.connection.requestAirdrop(superOwner.publicKey, 9000000000),
       "confirmed"
@@ -65,65 +183,29 @@ describe('staking_program', () => {
     console.log("super owner =", superOwner.publicKey.toBase58());
     console.log("user =", user.publicKey.toBase58());
 
-    // nft mint
-    nft_token_mint = await Token.createMint(
-      provider.connection,
-      user,
-      superOwner.publicKey,
-      null,
-      0,
-      TOKEN_PROGRAM_ID
-    );
-    user_nft_token_account = await nft_token_mint.createAccount(user.publicKey);
+    collection_mint = (await createNft(superOwner.publicKey, null)).mint;
+    ({ mint: nft_token_mint, token_account: user_nft_token_account } =
+      await createNft(user.publicKey, collection_mint));
 
-    await nft_token_mint.mintTo(
-      user_nft_token_account,
-      superOwner,
-      [],
-      1
-    );
+    // has a class leaf but no verified collection
+    outsider_nft = await createNft(user.publicKey, null);
+
+    let _user_nft_token_account = await nft_token_mint.getAccountInfo(user_nft_token_account);
+    assert.ok(Number(_user_nft_token_account.amount) == 1);
 
     console.log("create nft token!");
 
     // token mint
     reward_mint = await Token.createMint(
       provider.connection,
-      user,
+      superOwner,
       superOwner.publicKey,
       null,
       SWRD_DECIMAL,
       TOKEN_PROGRAM_ID
     );
 
-    // user_reward_account = await reward_mint.createAccount(user.publicKey);
-    // funder_vault_account = await reward_mint.createAccount(superOwner.publicKey);
-
-    // associate test
-    user_reward_account = await reward_mint.createAssociatedTokenAccount(user.publicKey);
     funder_vault_account = await reward_mint.createAssociatedTokenAccount(superOwner.publicKey);
-
-    user_reward_account = (await PublicKey.findProgramAddress(
-      [
-        user.publicKey.toBuffer(),
-        TOKEN_PROGRAM_ID.toBuffer(),
-        reward_mint.publicKey.toBuffer(), // mint address
-      ],
-      ASSOCIATED_TOKEN_PROGRAM_ID
-    ))[0];
-
-    funder_vault_account = (await PublicKey.findProgramAddress(
-      [
-        superOwner.publicKey.toBuffer(),
-        TOKEN_PROGRAM_ID.toBuffer(),
-        reward_mint.publicKey.toBuffer(), // mint address
-      ],
-      ASSOCIATED_TOKEN_PROGRAM_ID
-    ))[0];
-    // console.log("funder_vault_account_ass = ", funder_vault_account_ass);
-    console.log("funder_vault_account = ", funder_vault_account);
-    // console.log("associatedTokenAccountPubkey = ", associatedTokenAccountPubkey);
-    // test end
-
     await reward_mint.mintTo(
       funder_vault_account,
       superOwner,
@@ -134,22 +216,20 @@ describe('staking_program', () => {
     console.log("create reward token!");
 
     let _funder_vault_account = await reward_mint.getAccountInfo(funder_vault_account);
-    let _user_nft_token_account = await nft_token_mint.getAccountInfo(user_nft_token_account);
-
     assert.ok(Number(_funder_vault_account.amount) == initial_reward_vault_amount);
-    assert.ok(Number(_user_nft_token_account.amount) == 1);
 
     // create PDAs
-    const [pool_account_pda, bump] = await PublicKey.findProgramAddress(
-      [Buffer.from(RS_PREFIX)],
+    [pool_account_pda] = await PublicKey.findProgramAddress(
+      [Buffer.from(RS_PREFIX), pool_id.toArrayLike(Buffer, "le", 8)],
       program.programId
     );
 
     console.log("pool_account =", pool_account_pda.toBase58());
 
-    const [vault_pda, walletBump] = await PublicKey.findProgramAddress(
+    [vault_pda] = await PublicKey.findProgramAddress(
       [
         Buffer.from(RS_VAULT_SEED),
+        pool_account_pda.toBuffer(),
         reward_mint.publicKey.toBuffer(),
       ],
       program.programId
@@ -157,7 +237,15 @@ describe('staking_program', () => {
 
     console.log("vault_key =", vault_pda.toBase58());
 
-    const res = await program.methods.initializeStakingPool(class_types, lock_day).accounts({
+    const res = await program.methods.initializeStakingPool(
+      pool_id,
+      collection_mint.publicKey,
+      Array.from(Buffer.alloc(32)),
+      { escrow: {} },
+      { perNft: {} },
+      new anchor.BN(0),
+      new anchor.BN(DEFAULT_CONFIG_DELAY),
+    ).accounts({
       admin: superOwner.publicKey,
       poolAccount: pool_account_pda,
       rewardMint: reward_mint.publicKey,
@@ -170,31 +258,18 @@ describe('staking_program', () => {
     let _pool_config = await program.account.poolConfig.fetch(pool_account_pda);
 
     console.log("superOwner from contract = ", _pool_config.admin.toBase58());
-    assert.ok(_pool_config.admin.toBase58() == superOwner.publicKey.toBase58());
+    assert.ok(_pool_config.admin.equals(superOwner.publicKey));
     assert.ok(_pool_config.rewardMint.equals(reward_mint.publicKey));
     assert.ok(_pool_config.rewardVault.equals(vault_pda));
-    console.log("second class id: ", _pool_config.rewardPolicyByClass[1]);
-    assert.ok(_pool_config.rewardPolicyByClass[1] == class_types[1]);
+    assert.ok(_pool_config.collection.equals(collection_mint.publicKey));
     assert.ok(_pool_config.stakedNft == 0);
+    assert.ok(_pool_config.classCount == 0);
+    assert.ok(_pool_config.configDelay.toNumber() == DEFAULT_CONFIG_DELAY);
 
     console.log("Your transaction signature", res);
   });
 
   it("deposit reward", async () => {
-    // create PDAs
-    const [pool_account_pda, bump] = await PublicKey.findProgramAddress(
-      [Buffer.from(RS_PREFIX)],
-      program.programId
-    );
-
-    const [vault_pda, walletBump] = await PublicKey.findProgramAddress(
-      [
-        Buffer.from(RS_VAULT_SEED),
-        reward_mint.publicKey.toBuffer(),
-      ],
-      program.programId
-    );
-
     const ix = await program.methods.depositSwrd(
       new anchor.BN(deposit_amount)
     ).accounts({
@@ -214,38 +289,385 @@ describe('staking_program', () => {
     console.log("Your transaction signature", ix);
   })
 
-  it("Stake Nft", async () => {
-    const [pool_account_pda, bump] = await PublicKey.findProgramAddress(
-      [Buffer.from(RS_PREFIX)],
+  it("Add class", async () => {
+    [class_pda] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from(RS_CLASS_SEED),
+        pool_account_pda.toBuffer(),
+        u32Bytes(0),
+      ],
+      program.programId
+    );
+
+    const ix = await program.methods.addClass(classParams("common", 10, 0, 0, 0)).accounts({
+      configManager: superOwner.publicKey,
+      poolAccount: pool_account_pda,
+      classConfig: class_pda,
+      systemProgram: SystemProgram.programId,
+    }).signers([superOwner]).rpc();
+    console.log("Your transaction signature", ix);
+
+    let _class_config = await program.account.classConfig.fetch(class_pda);
+    assert.ok(_class_config.classId == 0);
+    assert.ok(_class_config.enabled);
+    assert.ok(_class_config.rewardPerDay.toNumber() == 10);
+    // new classes only take stakes once the config delay has passed
+    assert.ok(
+      _class_config.activeFrom.sub(_class_config.lastAccrualTime).toNumber() == DEFAULT_CONFIG_DELAY
+    );
+
+    let _pool_config = await program.account.poolConfig.fetch(pool_account_pda);
+    assert.ok(_pool_config.classCount == 1);
+  })
+
+  it("Reject a class with both a penalty and a cooldown", async () => {
+    const [next_class_pda] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from(RS_CLASS_SEED),
+        pool_account_pda.toBuffer(),
+        u32Bytes(1),
+      ],
+      program.programId
+    );
+
+    await expectError(
+      program.methods.addClass(classParams("unbonding", 10, 7, 3, 1_000)).accounts({
+        configManager: superOwner.publicKey,
+        poolAccount: pool_account_pda,
+        classConfig: next_class_pda,
+        systemProgram: SystemProgram.programId,
+      }).signers([superOwner]).rpc(),
+      "PenaltyWithCooldown"
+    );
+  })
+
+  it("Class update waits out the config delay", async () => {
+    const ix = await program.methods.queueClassUpdate(classParams("common", 20, 0, 0, 0)).accounts({
+      configManager: superOwner.publicKey,
+      poolAccount: pool_account_pda,
+      classConfig: class_pda,
+    }).signers([superOwner]).rpc();
+    console.log("Your transaction signature", ix);
+
+    await expectError(
+      program.methods.executeClassUpdate().accounts({
+        executor: superOwner.publicKey,
+        poolAccount: pool_account_pda,
+        classConfig: class_pda,
+      }).signers([superOwner]).rpc(),
+      "ConfigChangeLocked"
+    );
+
+    let _class_config = await program.account.classConfig.fetch(class_pda);
+    assert.ok(_class_config.rewardPerDay.toNumber() == 10);
+    assert.ok(_class_config.queuedUpdate.params.rewardPerDay.toNumber() == 20);
+  })
+
+  it("Class root change waits out the config delay", async () => {
+    const class_root = Array.from(anchor.web3.Keypair.generate().publicKey.toBuffer());
+    const ix = await program.methods.changeClassRoot(class_root).accounts({
+      admin: superOwner.publicKey,
+      poolAccount: pool_account_pda,
+    }).signers([superOwner]).rpc();
+    console.log("Your transaction signature", ix);
+
+    await expectError(
+      program.methods.executeClassRootChange().accounts({
+        executor: superOwner.publicKey,
+        poolAccount: pool_account_pda,
+      }).signers([superOwner]).rpc(),
+      "ConfigChangeLocked"
+    );
+
+    let _pool_config = await program.account.poolConfig.fetch(pool_account_pda);
+    assert.deepEqual(_pool_config.classRoot, Array.from(Buffer.alloc(32)));
+    assert.deepEqual(_pool_config.queuedClassRoot.classRoot, class_root);
+  })
+
+  it("Paused deposits are rejected", async () => {
+    await program.methods.setPaused(PAUSE_DEPOSIT).accounts({
+      pauser: superOwner.publicKey,
+      poolAccount: pool_account_pda,
+    }).signers([superOwner]).rpc();
+
+    await expectError(
+      program.methods.depositSwrd(new anchor.BN(1)).accounts({
+        funder: superOwner.publicKey,
+        rewardVault: vault_pda,
+        funderAccount: funder_vault_account,
+        poolAccount: pool_account_pda,
+        rewardMint: reward_mint.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([superOwner]).rpc(),
+      "OperationPaused"
+    );
+
+    await program.methods.setPaused(0).accounts({
+      pauser: superOwner.publicKey,
+      poolAccount: pool_account_pda,
+    }).signers([superOwner]).rpc();
+  })
+
+  it("withdraw reward through a proposal", async () => {
+    let _pool_config = await program.account.poolConfig.fetch(pool_account_pda);
+    const [proposal_pda] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from(RS_PROPOSAL_SEED),
+        pool_account_pda.toBuffer(),
+        _pool_config.proposalCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    // the proposer approves on creation, which meets the default threshold of 1
+    await program.methods.createProposal({
+      withdrawReward: { amount: new anchor.BN(deposit_amount), treasurer: superOwner.publicKey },
+    }).accounts({
+      proposer: superOwner.publicKey,
+      poolAccount: pool_account_pda,
+      proposal: proposal_pda,
+      systemProgram: SystemProgram.programId,
+    }).signers([superOwner]).rpc();
+
+    // accounts of `withdraw_swrd`, in order
+    const ix = await program.methods.executeProposal().accounts({
+      executor: superOwner.publicKey,
+      poolAccount: pool_account_pda,
+      proposal: proposal_pda,
+    }).remainingAccounts([
+      { pubkey: superOwner.publicKey, isSigner: true, isWritable: true },
+      { pubkey: superOwner.publicKey, isSigner: false, isWritable: false },
+      { pubkey: pool_account_pda, isSigner: false, isWritable: true },
+      { pubkey: vault_pda, isSigner: false, isWritable: true },
+      { pubkey: funder_vault_account, isSigner: false, isWritable: true },
+      { pubkey: reward_mint.publicKey, isSigner: false, isWritable: false },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+    ]).signers([superOwner]).rpc();
+
+    let _proposal = await program.account.proposal.fetch(proposal_pda);
+    assert.ok(_proposal.executed);
+    let _vault_account = await reward_mint.getAccountInfo(vault_pda);
+    assert.ok(Number(_vault_account.amount) == 0);
+    let _funder_account = await reward_mint.getAccountInfo(funder_vault_account);
+    assert.ok(Number(_funder_account.amount) == initial_reward_vault_amount);
+    console.log("remain amount: ", Number(_funder_account.amount));
+    console.log("Your transaction signature", ix);
+  })
+
+  it("Treasury proposals pay only the treasurer they name", async () => {
+    let _pool_config = await program.account.poolConfig.fetch(pool_account_pda);
+    const [proposal_pda] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from(RS_PROPOSAL_SEED),
+        pool_account_pda.toBuffer(),
+        _pool_config.proposalCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods.createProposal({
+      withdrawReward: { amount: new anchor.BN(0), treasurer: user.publicKey },
+    }).accounts({
+      proposer: superOwner.publicKey,
+      poolAccount: pool_account_pda,
+      proposal: proposal_pda,
+      systemProgram: SystemProgram.programId,
+    }).signers([superOwner]).rpc();
+
+    await expectError(
+      program.methods.executeProposal().accounts({
+        executor: superOwner.publicKey,
+        poolAccount: pool_account_pda,
+        proposal: proposal_pda,
+      }).remainingAccounts([
+        { pubkey: superOwner.publicKey, isSigner: true, isWritable: true },
+        { pubkey: superOwner.publicKey, isSigner: false, isWritable: false },
+        { pubkey: pool_account_pda, isSigner: false, isWritable: true },
+        { pubkey: vault_pda, isSigner: false, isWritable: true },
+        { pubkey: funder_vault_account, isSigner: false, isWritable: true },
+        { pubkey: reward_mint.publicKey, isSigner: false, isWritable: false },
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+      ]).signers([superOwner]).rpc(),
+      "InvalidProposal"
+    );
+  })
+
+  it("Changing the signer set makes open proposals stale", async () => {
+    const co_signer = anchor.web3.Keypair.generate();
+    let _pool_config = await program.account.poolConfig.fetch(pool_account_pda);
+    const [open_proposal_pda] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from(RS_PROPOSAL_SEED),
+        pool_account_pda.toBuffer(),
+        _pool_config.proposalCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [multisig_proposal_pda] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from(RS_PROPOSAL_SEED),
+        pool_account_pda.toBuffer(),
+        _pool_config.proposalCount.addn(1).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods.createProposal({
+      transferOwnership: { newAdmin: co_signer.publicKey },
+    }).accounts({
+      proposer: superOwner.publicKey,
+      poolAccount: pool_account_pda,
+      proposal: open_proposal_pda,
+      systemProgram: SystemProgram.programId,
+    }).signers([superOwner]).rpc();
+
+    const signers = [superOwner.publicKey, co_signer.publicKey, PublicKey.default, PublicKey.default, PublicKey.default];
+    await program.methods.createProposal({
+      changeMultisig: { signers, threshold: 2 },
+    }).accounts({
+      proposer: superOwner.publicKey,
+      poolAccount: pool_account_pda,
+      proposal: multisig_proposal_pda,
+      systemProgram: SystemProgram.programId,
+    }).signers([superOwner]).rpc();
+    await program.methods.executeProposal().accounts({
+      executor: superOwner.publicKey,
+      poolAccount: pool_account_pda,
+      proposal: multisig_proposal_pda,
+    }).signers([superOwner]).rpc();
+
+    _pool_config = await program.account.poolConfig.fetch(pool_account_pda);
+    assert.ok(_pool_config.multisigThreshold == 2);
+    assert.ok(_pool_config.multisigVersion == 1);
+
+    // approved under the old signer set
+    await expectError(
+      program.methods.executeProposal().accounts({
+        executor: superOwner.publicKey,
+        poolAccount: pool_account_pda,
+        proposal: open_proposal_pda,
+      }).remainingAccounts([
+        { pubkey: pool_account_pda, isSigner: false, isWritable: true },
+      ]).signers([superOwner]).rpc(),
+      "StaleProposal"
+    );
+    _pool_config = await program.account.poolConfig.fetch(pool_account_pda);
+    assert.ok(_pool_config.pendingAdmin.equals(PublicKey.default));
+  })
+
+  it("Initialize a pool without a config delay", async () => {
+    [staking_pool_pda] = await PublicKey.findProgramAddress(
+      [Buffer.from(RS_PREFIX), staking_pool_id.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [staking_vault_pda] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from(RS_VAULT_SEED),
+        staking_pool_pda.toBuffer(),
+        reward_mint.publicKey.toBuffer(),
+      ],
       program.programId
     );
+    [staking_class_pda] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from(RS_CLASS_SEED),
+        staking_pool_pda.toBuffer(),
+        u32Bytes(0),
+      ],
+      program.programId
+    );
+
+    await program.methods.initializeStakingPool(
+      staking_pool_id,
+      collection_mint.publicKey,
+      classRoot(classLeaf(nft_token_mint.publicKey, 0), classLeaf(outsider_nft.mint.publicKey, 0)),
+      { escrow: {} },
+      { perNft: {} },
+      new anchor.BN(0),
+      new anchor.BN(0),
+    ).accounts({
+      admin: superOwner.publicKey,
+      poolAccount: staking_pool_pda,
+      rewardMint: reward_mint.publicKey,
+      rewardVault: staking_vault_pda,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY
+    }).signers([superOwner]).rpc();
 
-    const [staked_nft_pda, staked_bump] = await PublicKey.findProgramAddress(
+    await program.methods.depositSwrd(
+      new anchor.BN(deposit_amount)
+    ).accounts({
+      funder: superOwner.publicKey,
+      rewardVault: staking_vault_pda,
+      funderAccount: funder_vault_account,
+      poolAccount: staking_pool_pda,
+      rewardMint: reward_mint.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([superOwner]).rpc();
+
+    const ix = await program.methods.addClass(classParams("common", 10, 0, 0, 0)).accounts({
+      configManager: superOwner.publicKey,
+      poolAccount: staking_pool_pda,
+      classConfig: staking_class_pda,
+      systemProgram: SystemProgram.programId,
+    }).signers([superOwner]).rpc();
+    console.log("Your transaction signature", ix);
+
+    let _pool_config = await program.account.poolConfig.fetch(staking_pool_pda);
+    assert.ok(_pool_config.configDelay.toNumber() == 0);
+    let _class_config = await program.account.classConfig.fetch(staking_class_pda);
+    assert.ok(_class_config.activeFrom.eq(_class_config.lastAccrualTime));
+  })
+
+  it("Stake Nft", async () => {
+    const [staked_nft_pda] = await PublicKey.findProgramAddress(
       [
         Buffer.from(RS_STAKE_SEED),
+        staking_pool_pda.toBuffer(),
         nft_token_mint.publicKey.toBuffer(),
       ],
       program.programId
     );
-
-    const [stake_info_pda, stake_info_bump] = await PublicKey.findProgramAddress(
+    const [stake_info_pda] = await PublicKey.findProgramAddress(
       [
         Buffer.from(RS_STAKEINFO_SEED),
+        staking_pool_pda.toBuffer(),
         nft_token_mint.publicKey.toBuffer(),
       ],
       program.programId
     );
+    const [user_state_pda] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from(RS_USER_STATE_SEED),
+        staking_pool_pda.toBuffer(),
+        user.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
 
-    const ix = await program.methods.stakeNft(2).accounts({
+    const proof = [Array.from(classLeaf(outsider_nft.mint.publicKey, 0))];
+    const ix = await program.methods.stakeNft(0, proof).accounts({
       owner: user.publicKey,
-      poolAccount: pool_account_pda,
-      nftMint: nft_token_mint.publicKey,
+      poolAccount: staking_pool_pda,
+      classConfig: staking_class_pda,
+      userState: user_state_pda,
       userNftTokenAccount: user_nft_token_account,
       destNftTokenAccount: staked_nft_pda,
       nftStakeInfoAccount: stake_info_pda,
-      rent: SYSVAR_RENT_PUBKEY,
-      systemProgram: SystemProgram.programId,
+      nftMint: nft_token_mint.publicKey,
+      nftMetadata: await metadataPda(nft_token_mint.publicKey),
+      nftEdition: await editionPda(nft_token_mint.publicKey),
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
       tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
     })
       .signers([user])
       .rpc();
@@ -254,8 +676,8 @@ describe('staking_program', () => {
     let _stakeinfo = await program.account.stakeInfo.fetch(stake_info_pda);
     assert.ok(_stakeinfo.owner.equals(user.publicKey));
     assert.ok(_stakeinfo.nftAddr.equals(nft_token_mint.publicKey));
-    console.log("class_id: ", _stakeinfo.classId);
-    console.log("stake_time : ", _stakeinfo.stakeTime);
+    assert.ok(_stakeinfo.classId == 0);
+    console.log("stake_time : ", _stakeinfo.stakeTime.toNumber());
 
     let _destNftTokenAccount = await nft_token_mint.getAccountInfo(staked_nft_pda);
     assert.ok(Number(_destNftTokenAccount.amount) == 1);
@@ -263,42 +685,88 @@ describe('staking_program', () => {
     let _user_nft_token_account = await nft_token_mint.getAccountInfo(user_nft_token_account);
     assert.ok(Number(_user_nft_token_account.amount) == 0);
 
+    let _user_state = await program.account.userState.fetch(user_state_pda);
+    assert.ok(_user_state.stakedCount == 1);
   })
 
-  it("Claim reward", async () => {
-    const [pool_account_pda, bump] = await PublicKey.findProgramAddress(
-      [Buffer.from(RS_PREFIX)],
+  it("Reject an nft outside the collection", async () => {
+    const { mint, token_account } = outsider_nft;
+    const [staked_nft_pda] = await PublicKey.findProgramAddress(
+      [Buffer.from(RS_STAKE_SEED), staking_pool_pda.toBuffer(), mint.publicKey.toBuffer()],
       program.programId
     );
-
-    const [vault_pda, walletBump] = await PublicKey.findProgramAddress(
-      [
-        Buffer.from(RS_VAULT_SEED),
-        reward_mint.publicKey.toBuffer(),
-      ],
+    const [stake_info_pda] = await PublicKey.findProgramAddress(
+      [Buffer.from(RS_STAKEINFO_SEED), staking_pool_pda.toBuffer(), mint.publicKey.toBuffer()],
+      program.programId
+    );
+    const [user_state_pda] = await PublicKey.findProgramAddress(
+      [Buffer.from(RS_USER_STATE_SEED), staking_pool_pda.toBuffer(), user.publicKey.toBuffer()],
       program.programId
     );
 
-    const [stake_info_pda, stake_info_bump] = await PublicKey.findProgramAddress(
+    const proof = [Array.from(classLeaf(nft_token_mint.publicKey, 0))];
+    await expectError(
+      program.methods.stakeNft(0, proof).accounts({
+        owner: user.publicKey,
+        poolAccount: staking_pool_pda,
+        classConfig: staking_class_pda,
+        userState: user_state_pda,
+        userNftTokenAccount: token_account,
+        destNftTokenAccount: staked_nft_pda,
+        nftStakeInfoAccount: stake_info_pda,
+        nftMint: mint.publicKey,
+        nftMetadata: await metadataPda(mint.publicKey),
+        nftEdition: await editionPda(mint.publicKey),
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).signers([user]).rpc(),
+      "InvalidCollection"
+    );
+  })
+
+  it("Claim reward", async () => {
+    const [stake_info_pda] = await PublicKey.findProgramAddress(
       [
         Buffer.from(RS_STAKEINFO_SEED),
+        staking_pool_pda.toBuffer(),
         nft_token_mint.publicKey.toBuffer(),
       ],
       program.programId
     );
+    const [user_state_pda] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from(RS_USER_STATE_SEED),
+        staking_pool_pda.toBuffer(),
+        user.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    user_reward_account = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      TOKEN_PROGRAM_ID,
+      reward_mint.publicKey,
+      user.publicKey
+    );
+
+    // let a few seconds of reward accrue
+    await new Promise((resolve) => setTimeout(resolve, 3000));
 
     const ix = await program.methods.claimReward().accounts({
       owner: user.publicKey,
-      poolAccount: pool_account_pda,
-      rewardMint: reward_mint.publicKey,
-      nftMint: nft_token_mint.publicKey,
+      poolAccount: staking_pool_pda,
+      userState: user_state_pda,
       nftStakeInfoAccount: stake_info_pda,
+      classConfig: staking_class_pda,
+      rewardVault: staking_vault_pda,
+      rewardMint: reward_mint.publicKey,
       rewardToAccount: user_reward_account,
-      rewardVault: vault_pda,
+      nftMint: nft_token_mint.publicKey,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-      rent: SYSVAR_RENT_PUBKEY,
-      systemProgram: SystemProgram.programId,
       tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
     })
       .signers([user])
       .rpc();
@@ -306,52 +774,55 @@ describe('staking_program', () => {
 
     let _user_reward_account = await reward_mint.getAccountInfo(user_reward_account);
     console.log("reward amount: ", Number(_user_reward_account.amount));
-
+    assert.ok(Number(_user_reward_account.amount) > 0);
   })
 
   it("Withdraw Nft", async () => {
-    const [pool_account_pda, bump] = await PublicKey.findProgramAddress(
-      [Buffer.from(RS_PREFIX)],
-      program.programId
-    );
-
-    const [vault_pda, walletBump] = await PublicKey.findProgramAddress(
+    const [staked_nft_pda] = await PublicKey.findProgramAddress(
       [
-        Buffer.from(RS_VAULT_SEED),
-        reward_mint.publicKey.toBuffer(),
+        Buffer.from(RS_STAKE_SEED),
+        staking_pool_pda.toBuffer(),
+        nft_token_mint.publicKey.toBuffer(),
       ],
       program.programId
     );
-
-    const [staked_nft_pda, staked_bump] = await PublicKey.findProgramAddress(
+    const [stake_info_pda] = await PublicKey.findProgramAddress(
       [
-        Buffer.from(RS_STAKE_SEED),
+        Buffer.from(RS_STAKEINFO_SEED),
+        staking_pool_pda.toBuffer(),
         nft_token_mint.publicKey.toBuffer(),
       ],
       program.programId
     );
-
-    const [stake_info_pda, stake_info_bump] = await PublicKey.findProgramAddress(
+    const [user_state_pda] = await PublicKey.findProgramAddress(
       [
-        Buffer.from(RS_STAKEINFO_SEED),
-        nft_token_mint.publicKey.toBuffer(),
+        Buffer.from(RS_USER_STATE_SEED),
+        staking_pool_pda.toBuffer(),
+        user.publicKey.toBuffer(),
       ],
       program.programId
     );
+    const claimed = Number((await reward_mint.getAccountInfo(user_reward_account)).amount);
 
     const ix = await program.methods.withdrawNft().accounts({
       owner: user.publicKey,
-      poolAccount: pool_account_pda,
-      nftMint: nft_token_mint.publicKey,
+      poolAccount: staking_pool_pda,
+      userState: user_state_pda,
+      rewardVault: staking_vault_pda,
+      rewardMint: reward_mint.publicKey,
+      nftStakeInfoAccount: stake_info_pda,
+      classConfig: staking_class_pda,
       userNftTokenAccount: user_nft_token_account,
       stakedNftTokenAccount: staked_nft_pda,
-      nftStakeInfoAccount: stake_info_pda,
       rewardToAccount: user_reward_account,
-      rewardVault: vault_pda,
-      rewardMint: reward_mint.publicKey,
-      rent: SYSVAR_RENT_PUBKEY,
-      systemProgram: SystemProgram.programId,
+      penaltyTreasury: staking_vault_pda,
+      nftMint: nft_token_mint.publicKey,
+      nftEdition: await editionPda(nft_token_mint.publicKey),
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
     })
       .signers([user])
       .rpc();
@@ -361,40 +832,12 @@ describe('staking_program', () => {
     assert.ok(Number(_user_nft_token_account.amount) == 1);
     let _user_reward_account = await reward_mint.getAccountInfo(user_reward_account);
     console.log("reward amount: ", Number(_user_reward_account.amount));
+    assert.ok(Number(_user_reward_account.amount) >= claimed);
 
-  })
-
-  it("withdraw reward", async () => {
-    const [vault_pda, walletBump] = await PublicKey.findProgramAddress(
-      [
-        Buffer.from(RS_VAULT_SEED),
-        reward_mint.publicKey.toBuffer(),
-      ],
-      program.programId
-    );
-
-    const [pool_account_pda, bump] = await PublicKey.findProgramAddress(
-      [Buffer.from(RS_PREFIX)],
-      program.programId
-    );
-
-    const ix = await program.methods.withdrawSwrd().accounts({
-      admin: superOwner.publicKey,
-      poolAccount: pool_account_pda,
-      rewardVault: vault_pda,
-      rewardToAccount: funder_vault_account,
-      rewardMint: reward_mint.publicKey,
-      tokenProgram: TOKEN_PROGRAM_ID,
-      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-      systemProgram: SystemProgram.programId,
-      rent: SYSVAR_RENT_PUBKEY,
-    }).signers([superOwner]).rpc();
-
-    let _vault_account = await reward_mint.getAccountInfo(vault_pda);
-    assert.ok(Number(_vault_account.amount) == 0);
-    let _funder_account = await reward_mint.getAccountInfo(funder_vault_account);
-    console.log("deposit amount: ", Number(_vault_account.amount));
-    console.log("remain amount: ", Number(_funder_account.amount));
-    console.log("Your transaction signature", ix);
+    let _pool_config = await program.account.poolConfig.fetch(staking_pool_pda);
+    assert.ok(_pool_config.stakedNft == 0);
+    let _user_state = await program.account.userState.fetch(user_state_pda);
+    assert.ok(_user_state.stakedCount == 0);
+    assert.ok((await provider.connection.getAccountInfo(stake_info_pda)) == null);
   })
 });